@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::config::{Automaton, Settings};
+use crate::{colors, generations, lenia, turmite, wireworld, GameData};
+
+/// Generations it takes to fade through the whole gradient.
+const AGE_SPAN: f32 = 64.0;
+
+/// Colors a cell passes through as it ages, after starting at the cell color.
+const AGE_GRADIENT: [Color; 3] = [
+    Color::rgb(1.0, 0.8, 0.2),
+    Color::rgb(0.9, 0.3, 0.1),
+    Color::rgb(0.3, 0.1, 0.4),
+];
+
+/// How many generations the cell at `[x, y]` has been alive, or `0` if it
+/// was painted or loaded since the last step.
+pub fn age(game_data: &GameData, x: usize, y: usize) -> u16 {
+    game_data
+        .ages
+        .get(y * game_data.board.width() + x)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Advances every live cell's age by one and resets dead cells to zero.
+/// Called after each step.
+pub fn update_ages(game_data: &mut GameData) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    game_data.ages.resize(width * height, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let age = &mut game_data.ages[y * width + x];
+            *age = if game_data.board.get(x, y) {
+                age.saturating_add(1)
+            } else {
+                0
+            };
+        }
+    }
+}
+
+/// The color a live cell is drawn in, taking its age into account when
+/// [`Settings::color_by_age`] is on.
+pub fn live_cell_color(game_data: &GameData, settings: &Settings, x: usize, y: usize) -> Color {
+    let base = settings.cell_color();
+    if !settings.color_by_age {
+        return base;
+    }
+    let stops = [base, AGE_GRADIENT[0], AGE_GRADIENT[1], AGE_GRADIENT[2]]
+        .map(|color| Vec4::from(color.as_rgba_f32()));
+    let t = (age(game_data, x, y).saturating_sub(1) as f32 / AGE_SPAN).min(1.0)
+        * (stops.len() - 1) as f32;
+    let i = (t as usize).min(stops.len() - 2);
+    let rgba = stops[i].lerp(stops[i + 1], t - i as f32);
+    Color::rgba(rgba.x, rgba.y, rgba.z, rgba.w)
+}
+
+/// The color the cell at `[x, y]` is drawn in, or `None` for a dead cell.
+pub fn cell_color(game_data: &GameData, settings: &Settings, x: usize, y: usize) -> Option<Color> {
+    if game_data.automaton == Automaton::Wireworld {
+        wireworld::wire_state(game_data, x, y).color()
+    } else if game_data.automaton == Automaton::Lenia {
+        lenia::color(game_data, settings, x, y)
+    } else if game_data.automaton == Automaton::Turmite {
+        let color = turmite::cell_color_index(game_data, x, y);
+        turmite::display_color(game_data, settings, color)
+    } else if game_data.board.get(x, y) && colors::color_count(game_data.automaton) > 0 {
+        Some(colors::color(game_data, x, y))
+    } else if game_data.board.get(x, y) {
+        Some(live_cell_color(game_data, settings, x, y))
+    } else {
+        generations::dying_color(game_data, settings, x, y)
+    }
+}
+
+/// `A` toggles coloring cells by age.
+pub fn toggle_age_coloring(actions: Actions, mut settings: ResMut<Settings>) {
+    if actions.just_pressed(Action::ColorByAge) {
+        settings.color_by_age = !settings.color_by_age;
+    }
+}
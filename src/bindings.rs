@@ -0,0 +1,362 @@
+//! Named actions and the keys or mouse buttons that trigger them.
+//!
+//! The defaults are in [`Keybindings::default`]. A `[keybindings]` table in
+//! the config file replaces the bindings of each action it lists and leaves
+//! the rest alone, for example:
+//!
+//! ```toml
+//! [keybindings]
+//! pause = ["P", "Space"]
+//! draw = ["MouseLeft"]
+//! save = ["Ctrl+S"]
+//! ```
+//!
+//! Keys use the names of bevy's `KeyCode` variants (`A`, `Key1`, `F11`,
+//! `LBracket`, ...) and mouse buttons are `MouseLeft`, `MouseRight`,
+//! `MouseMiddle` or `Mouse<n>`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Pause,
+    Draw,
+    Select,
+    Pan,
+    CycleBoundary,
+    Reseed,
+    Clear,
+    Fill,
+    Invert,
+    DensityUp,
+    DensityDown,
+    BrushBigger,
+    BrushSmaller,
+    CycleSymmetry,
+    Copy,
+    Cut,
+    Paste,
+    DeleteSelection,
+    RotateSelection,
+    FlipHorizontally,
+    FlipVertically,
+    NudgeLeft,
+    NudgeRight,
+    NudgeUp,
+    NudgeDown,
+    Cancel,
+    Save,
+    Load,
+    ExportMacrocell,
+    RecordAnimation,
+    Screenshot,
+    RecordReplay,
+    HistoryBack,
+    HistoryForward,
+    NextElementaryRule,
+    PreviousElementaryRule,
+    WireConductor,
+    WireHead,
+    WireTail,
+    WireEmpty,
+    PaintColor1,
+    PaintColor2,
+    PaintColor3,
+    PaintColor4,
+    ColorByAge,
+    CycleTheme,
+    GridLines,
+    Heatmap,
+    NeighborCounts,
+    PopulationGraph,
+    Audio,
+    FitCamera,
+    Follow,
+    Fullscreen,
+    SettingsPanel,
+    PatternSearch,
+    MainMenu,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Trigger {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+/// A key or mouse button, optionally with modifiers. Ctrl has to be held
+/// exactly when the binding names it, so `C` and `Ctrl+C` can do different
+/// things; Shift is only checked when named.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binding {
+    ctrl: bool,
+    shift: bool,
+    trigger: Trigger,
+}
+
+impl Binding {
+    const fn key(key: KeyCode) -> Self {
+        Binding {
+            ctrl: false,
+            shift: false,
+            trigger: Trigger::Key(key),
+        }
+    }
+
+    const fn ctrl(key: KeyCode) -> Self {
+        Binding {
+            ctrl: true,
+            ..Binding::key(key)
+        }
+    }
+
+    const fn mouse(button: MouseButton) -> Self {
+        Binding {
+            ctrl: false,
+            shift: false,
+            trigger: Trigger::Mouse(button),
+        }
+    }
+
+    fn modifiers_held(&self, keyboard: &Input<KeyCode>) -> bool {
+        let ctrl = keyboard.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+        let shift = keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+        ctrl == self.ctrl && (shift || !self.shift)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        match self.trigger {
+            Trigger::Key(key) => write!(f, "{key:?}"),
+            Trigger::Mouse(MouseButton::Other(n)) => write!(f, "Mouse{n}"),
+            Trigger::Mouse(button) => write!(f, "Mouse{button:?}"),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut ctrl, mut shift) = (false, false);
+        let mut name = s.trim();
+        loop {
+            if let Some(rest) = name.strip_prefix("Ctrl+") {
+                ctrl = true;
+                name = rest;
+            } else if let Some(rest) = name.strip_prefix("Shift+") {
+                shift = true;
+                name = rest;
+            } else {
+                break;
+            }
+        }
+        let trigger = match name.strip_prefix("Mouse") {
+            Some("Left") => Trigger::Mouse(MouseButton::Left),
+            Some("Right") => Trigger::Mouse(MouseButton::Right),
+            Some("Middle") => Trigger::Mouse(MouseButton::Middle),
+            Some(n) => Trigger::Mouse(MouseButton::Other(
+                n.parse()
+                    .map_err(|_| format!("unknown mouse button `{s}`"))?,
+            )),
+            None => Trigger::Key(
+                KeyCode::deserialize(StrDeserializer::<ValueError>::new(name))
+                    .map_err(|_| format!("unknown key `{s}`"))?,
+            ),
+        };
+        Ok(Binding {
+            ctrl,
+            shift,
+            trigger,
+        })
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+/// Every action's bindings. Copied out of [`crate::Settings`] into a
+/// resource of its own, so systems can check bindings while editing the
+/// settings.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<Action, Vec<Binding>>")]
+pub struct Keybindings(BTreeMap<Action, Vec<Binding>>);
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        use Action::*;
+        use KeyCode as K;
+        let bindings = [
+            (Pause, vec![Binding::key(K::Space)]),
+            (Draw, vec![Binding::mouse(MouseButton::Left)]),
+            (Select, vec![Binding::mouse(MouseButton::Right)]),
+            (Pan, vec![Binding::mouse(MouseButton::Middle)]),
+            (CycleBoundary, vec![Binding::key(K::B)]),
+            (Reseed, vec![Binding::key(K::R)]),
+            (Clear, vec![Binding::key(K::C)]),
+            (Fill, vec![Binding::key(K::F)]),
+            (Invert, vec![Binding::key(K::I)]),
+            (DensityUp, vec![Binding::key(K::Period)]),
+            (DensityDown, vec![Binding::key(K::Comma)]),
+            (BrushBigger, vec![Binding::key(K::RBracket)]),
+            (BrushSmaller, vec![Binding::key(K::LBracket)]),
+            (CycleSymmetry, vec![Binding::key(K::M)]),
+            (Copy, vec![Binding::ctrl(K::C)]),
+            (Cut, vec![Binding::ctrl(K::X)]),
+            (Paste, vec![Binding::ctrl(K::V)]),
+            (
+                DeleteSelection,
+                vec![Binding::key(K::Delete), Binding::key(K::Back)],
+            ),
+            (RotateSelection, vec![Binding::key(K::Q)]),
+            (FlipHorizontally, vec![Binding::key(K::X)]),
+            (FlipVertically, vec![Binding::key(K::Y)]),
+            (NudgeLeft, vec![Binding::key(K::Left)]),
+            (NudgeRight, vec![Binding::key(K::Right)]),
+            (NudgeUp, vec![Binding::key(K::Up)]),
+            (NudgeDown, vec![Binding::key(K::Down)]),
+            (Cancel, vec![Binding::key(K::Escape)]),
+            (Save, vec![Binding::ctrl(K::S)]),
+            (Load, vec![Binding::ctrl(K::O)]),
+            (ExportMacrocell, vec![Binding::ctrl(K::E)]),
+            (RecordAnimation, vec![Binding::key(K::F9)]),
+            (Screenshot, vec![Binding::key(K::F12)]),
+            (RecordReplay, vec![Binding::key(K::F5)]),
+            (HistoryBack, vec![Binding::key(K::Left)]),
+            (HistoryForward, vec![Binding::key(K::Right)]),
+            (NextElementaryRule, vec![Binding::key(K::Up)]),
+            (PreviousElementaryRule, vec![Binding::key(K::Down)]),
+            (WireConductor, vec![Binding::key(K::Key1)]),
+            (WireHead, vec![Binding::key(K::Key2)]),
+            (WireTail, vec![Binding::key(K::Key3)]),
+            (WireEmpty, vec![Binding::key(K::Key4)]),
+            (PaintColor1, vec![Binding::key(K::Key1)]),
+            (PaintColor2, vec![Binding::key(K::Key2)]),
+            (PaintColor3, vec![Binding::key(K::Key3)]),
+            (PaintColor4, vec![Binding::key(K::Key4)]),
+            (ColorByAge, vec![Binding::key(K::A)]),
+            (CycleTheme, vec![Binding::key(K::T)]),
+            (GridLines, vec![Binding::key(K::G)]),
+            (Heatmap, vec![Binding::key(K::H)]),
+            (NeighborCounts, vec![Binding::key(K::D)]),
+            (PopulationGraph, vec![Binding::key(K::P)]),
+            (Audio, vec![Binding::key(K::N)]),
+            (FitCamera, vec![Binding::key(K::Z)]),
+            (Follow, vec![Binding::key(K::L)]),
+            (Fullscreen, vec![Binding::key(K::F11)]),
+            (SettingsPanel, vec![Binding::key(K::Tab)]),
+            (PatternSearch, vec![Binding::key(K::F2)]),
+            (MainMenu, vec![Binding::key(K::F1)]),
+        ];
+        Keybindings(bindings.into_iter().collect())
+    }
+}
+
+/// The defaults, with every action in `overrides` rebound.
+impl From<BTreeMap<Action, Vec<Binding>>> for Keybindings {
+    fn from(overrides: BTreeMap<Action, Vec<Binding>>) -> Self {
+        let mut bindings = Keybindings::default();
+        bindings.0.extend(overrides);
+        bindings
+    }
+}
+
+impl Keybindings {
+    fn bindings(&self, action: Action) -> impl Iterator<Item = &Binding> {
+        self.0.get(&action).into_iter().flatten()
+    }
+
+    /// Whether any of `action`'s bindings is held.
+    pub fn pressed(
+        &self,
+        action: Action,
+        keyboard: &Input<KeyCode>,
+        mouse: &Input<MouseButton>,
+    ) -> bool {
+        self.bindings(action).any(|binding| {
+            binding.modifiers_held(keyboard)
+                && match binding.trigger {
+                    Trigger::Key(key) => keyboard.pressed(key),
+                    Trigger::Mouse(button) => mouse.pressed(button),
+                }
+        })
+    }
+
+    /// Whether any of `action`'s bindings was pressed this frame.
+    pub fn just_pressed(
+        &self,
+        action: Action,
+        keyboard: &Input<KeyCode>,
+        mouse: &Input<MouseButton>,
+    ) -> bool {
+        self.bindings(action).any(|binding| {
+            binding.modifiers_held(keyboard)
+                && match binding.trigger {
+                    Trigger::Key(key) => keyboard.just_pressed(key),
+                    Trigger::Mouse(button) => mouse.just_pressed(button),
+                }
+        })
+    }
+
+    /// Forgets `action`'s keys and buttons are down until they are pressed
+    /// again, so one press doesn't trigger later systems too.
+    pub fn release(
+        &self,
+        action: Action,
+        keyboard: &mut Input<KeyCode>,
+        mouse: &mut Input<MouseButton>,
+    ) {
+        for binding in self.bindings(action) {
+            match binding.trigger {
+                Trigger::Key(key) => keyboard.reset(key),
+                Trigger::Mouse(button) => mouse.reset(button),
+            }
+        }
+    }
+}
+
+/// Reads actions in systems that don't otherwise need the raw input.
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    bindings: Res<'w, Keybindings>,
+    keyboard: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+}
+
+impl Actions<'_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.bindings.pressed(action, &self.keyboard, &self.mouse)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.bindings
+            .just_pressed(action, &self.keyboard, &self.mouse)
+    }
+}
@@ -0,0 +1,737 @@
+use bevy::prelude::Resource;
+use clap::ValueEnum;
+use rand::Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::rule::Rule;
+
+/// What neighbor lookups see past the edge of the board.
+#[derive(
+    Resource, Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum BoundaryMode {
+    /// Opposite edges are joined, so patterns wrap around.
+    #[default]
+    Torus,
+    /// Everything outside the board is permanently dead.
+    DeadBorder,
+    /// The edge row or column is reflected outward.
+    Mirror,
+}
+
+impl BoundaryMode {
+    pub fn next(self) -> Self {
+        match self {
+            BoundaryMode::Torus => BoundaryMode::DeadBorder,
+            BoundaryMode::DeadBorder => BoundaryMode::Mirror,
+            BoundaryMode::Mirror => BoundaryMode::Torus,
+        }
+    }
+
+    /// Maps a possibly out-of-range coordinate on an axis of length `len`
+    /// back onto the board, or `None` if it lands on a dead border.
+    pub fn resolve(self, coord: isize, len: usize) -> Option<usize> {
+        let len = len as isize;
+        if (0..len).contains(&coord) {
+            return Some(coord as usize);
+        }
+        match self {
+            BoundaryMode::Torus => Some(coord.rem_euclid(len) as usize),
+            BoundaryMode::DeadBorder => None,
+            BoundaryMode::Mirror => {
+                let reflected = if coord < 0 {
+                    -coord - 1
+                } else {
+                    2 * len - coord - 1
+                };
+                Some(reflected.clamp(0, len - 1) as usize)
+            }
+        }
+    }
+}
+
+/// Rows in each band the board is stepped in. A band and one word of
+/// columns (64 cells) make up a chunk, the unit [`Board::step_active`] skips.
+const ROWS_PER_BAND: usize = 16;
+
+/// What [`Board::step_active`] needs from the previous step: the board it
+/// stepped from, the one it produced, and the rule and boundary it used.
+#[derive(Default)]
+pub struct Activity {
+    input: Board,
+    output: Board,
+    rule: Option<Rule>,
+    boundary: Option<BoundaryMode>,
+}
+
+/// A `width` x `height` grid of cells with `y = 0` at the bottom of the
+/// screen. Each row is packed into `u64` words, one bit per cell with cell
+/// `x` at bit `x % 64` of word `x / 64`. Bits past `width` are always zero.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        Board {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.words_per_row + x / 64] >> (x % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        let word = &mut self.words[y * self.words_per_row + x / 64];
+        if alive {
+            *word |= 1 << (x % 64);
+        } else {
+            *word &= !(1 << (x % 64));
+        }
+    }
+
+    /// Returns `[x, y]` if the signed coordinate lies on the board.
+    pub fn checked(&self, x: isize, y: isize) -> Option<[usize; 2]> {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some([x as usize, y as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn fill(&mut self, alive: bool) {
+        self.words.fill(if alive { u64::MAX } else { 0 });
+        self.clear_padding();
+    }
+
+    pub fn invert(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self.clear_padding();
+    }
+
+    /// Moves every row up by one, dropping the top row and leaving the
+    /// bottom row as it was.
+    pub fn scroll_up(&mut self) {
+        if self.height > 1 {
+            let rows = (self.height - 1) * self.words_per_row;
+            self.words.copy_within(0..rows, self.words_per_row);
+        }
+    }
+
+    /// Makes every cell alive with probability `density`.
+    pub fn randomize(&mut self, rng: &mut impl Rng, density: f64) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.set(x, y, rng.gen_bool(density.clamp(0.0, 1.0)));
+            }
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Mask of the bits in the last word of a row that belong to the board.
+    fn last_word_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// How many cells were born and how many died going from `previous` to
+    /// this board, which must be the same size.
+    pub fn changes_since(&self, previous: &Board) -> (usize, usize) {
+        let mut births = 0;
+        let mut deaths = 0;
+        for (&now, &before) in self.words.iter().zip(&previous.words) {
+            births += (now & !before).count_ones() as usize;
+            deaths += (before & !now).count_ones() as usize;
+        }
+        (births, deaths)
+    }
+
+    /// Inclusive `(min, max)` corners of the smallest rectangle holding every
+    /// live cell, or `None` if there are none.
+    pub fn bounding_box(&self) -> Option<([usize; 2], [usize; 2])> {
+        let mut bounds: Option<([usize; 2], [usize; 2])> = None;
+        for y in 0..self.height {
+            let row = self.row(y);
+            let Some(first) = row.iter().position(|&word| word != 0) else {
+                continue;
+            };
+            let last = row.iter().rposition(|&word| word != 0).unwrap_or(first);
+            let min_x = first * 64 + row[first].trailing_zeros() as usize;
+            let max_x = last * 64 + 63 - row[last].leading_zeros() as usize;
+            bounds = Some(match bounds {
+                None => ([min_x, y], [max_x, y]),
+                Some((min, max)) => ([min[0].min(min_x), min[1]], [max[0].max(max_x), y]),
+            });
+        }
+        bounds
+    }
+
+    /// Mean `[x, y]` of the live cells, or `None` if there are none.
+    pub fn centroid(&self) -> Option<[f64; 2]> {
+        let (mut count, mut sum_x, mut sum_y) = (0u64, 0u64, 0u64);
+        for y in 0..self.height {
+            for (i, &word) in self.row(y).iter().enumerate() {
+                let mut bits = word;
+                while bits != 0 {
+                    sum_x += (i * 64) as u64 + bits.trailing_zeros() as u64;
+                    sum_y += y as u64;
+                    count += 1;
+                    bits &= bits - 1;
+                }
+            }
+        }
+        (count > 0).then(|| [sum_x as f64 / count as f64, sum_y as f64 / count as f64])
+    }
+
+    fn clear_padding(&mut self) {
+        let mask = self.last_word_mask();
+        for row in self.words.chunks_mut(self.words_per_row) {
+            if let Some(last) = row.last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Returns the row shifted so that bit `x` holds the cell at `x - 1`
+    /// (`west`) and `x + 1` (`east`), with the cells past either edge taken
+    /// from `boundary`.
+    fn shifted(&self, row: &[u64], boundary: BoundaryMode, west: &mut [u64], east: &mut [u64]) {
+        let bit = |x: Option<usize>| x.map_or(0, |x| row[x / 64] >> (x % 64) & 1);
+        let past_west = bit(boundary.resolve(-1, self.width));
+        let past_east = bit(boundary.resolve(self.width as isize, self.width));
+        let last = self.words_per_row - 1;
+        for i in 0..self.words_per_row {
+            let previous = if i == 0 { past_west } else { row[i - 1] >> 63 };
+            let next = if i == last { 0 } else { row[i + 1] << 63 };
+            west[i] = row[i] << 1 | previous;
+            east[i] = row[i] >> 1 | next;
+        }
+        east[last] |= past_east << ((self.width - 1) % 64);
+        west[last] &= self.last_word_mask();
+    }
+
+    /// Whether the cell at a possibly out-of-range coordinate is alive, as
+    /// seen through `boundary`.
+    pub fn get_resolved(&self, x: isize, y: isize, boundary: BoundaryMode) -> bool {
+        match (
+            boundary.resolve(x, self.width),
+            boundary.resolve(y, self.height),
+        ) {
+            (Some(x), Some(y)) => self.get(x, y),
+            _ => false,
+        }
+    }
+
+    /// How many of the cell's neighbors under `rule`'s neighborhood are
+    /// alive, the count its next state is decided by.
+    pub fn neighbor_count(&self, x: usize, y: usize, rule: Rule, boundary: BoundaryMode) -> usize {
+        let r = rule.range as isize;
+        let mut count = 0;
+        for dy in -r..=r {
+            let reach = rule.neighborhood.reach(rule.range, dy) as isize;
+            for dx in -reach..=reach {
+                if (dx, dy) != (0, 0)
+                    && self.get_resolved(x as isize + dx, y as isize + dy, boundary)
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Computes the next generation into `next` one cell at a time, counting
+    /// the live cells at each of `offsets`. Slower than [`Board::step_into`]
+    /// but works for any neighborhood.
+    pub fn step_with_offsets(
+        &self,
+        next: &mut Board,
+        rule: Rule,
+        boundary: BoundaryMode,
+        offsets: &[[isize; 2]],
+    ) {
+        let words_per_row = self.words_per_row;
+        if words_per_row == 0 {
+            return;
+        }
+        next.words
+            .par_chunks_mut(words_per_row)
+            .enumerate()
+            .for_each(|(y, out)| {
+                out.fill(0);
+                for x in 0..self.width {
+                    let count = offsets
+                        .iter()
+                        .filter(|[dx, dy]| {
+                            self.get_resolved(x as isize + dx, y as isize + dy, boundary)
+                        })
+                        .count();
+                    if rule.next_state(self.get(x, y), count) {
+                        out[x / 64] |= 1 << (x % 64);
+                    }
+                }
+            });
+    }
+
+    /// Computes the next generation for a rule with a wider or non-Moore
+    /// neighborhood. Running sums along each padded row make every row of
+    /// the neighborhood cost two lookups however far it reaches.
+    pub fn step_range(&self, next: &mut Board, rule: Rule, boundary: BoundaryMode) {
+        let words_per_row = self.words_per_row;
+        if words_per_row == 0 {
+            return;
+        }
+        let range = rule.range;
+        let r = range as isize;
+        // `prefix[py * stride + px]` counts the live cells in padded row `py`
+        // left of padded column `px`; padding is filled in through `boundary`.
+        let stride = self.width + 2 * range + 1;
+        let mut prefix = vec![0u32; stride * (self.height + 2 * range)];
+        prefix
+            .par_chunks_mut(stride)
+            .enumerate()
+            .for_each(|(py, sums)| {
+                for px in 0..stride - 1 {
+                    let alive = self.get_resolved(px as isize - r, py as isize - r, boundary);
+                    sums[px + 1] = sums[px] + alive as u32;
+                }
+            });
+
+        next.words
+            .par_chunks_mut(words_per_row)
+            .enumerate()
+            .for_each(|(y, out)| {
+                out.fill(0);
+                for x in 0..self.width {
+                    let px = x + range;
+                    let total: u32 = (-r..=r)
+                        .map(|dy| {
+                            let sums = &prefix[(y as isize + r + dy) as usize * stride..];
+                            let reach = rule.neighborhood.reach(range, dy);
+                            sums[px + reach + 1] - sums[px - reach]
+                        })
+                        .sum();
+                    let alive = self.get(x, y);
+                    if rule.next_state(alive, total as usize - alive as usize) {
+                        out[x / 64] |= 1 << (x % 64);
+                    }
+                }
+            });
+    }
+
+    /// Computes the next generation into `next` (which must be the same
+    /// size), counting the eight Moore neighbors of 64 cells at a time.
+    pub fn step_into(&self, next: &mut Board, rule: Rule, boundary: BoundaryMode) {
+        self.step_chunks(next, rule, boundary, None);
+    }
+
+    /// Like [`Board::step_into`], but only recomputes the chunks near cells
+    /// that changed since the board `activity` last stepped. Everywhere
+    /// else the next generation is the same as the one computed last time.
+    pub fn step_active(
+        &self,
+        next: &mut Board,
+        rule: Rule,
+        boundary: BoundaryMode,
+        activity: &mut Activity,
+    ) {
+        let same_size = |board: &Board| board.width == self.width && board.height == self.height;
+        let reusable = activity.rule == Some(rule)
+            && activity.boundary == Some(boundary)
+            && same_size(&activity.input)
+            && same_size(&activity.output);
+        if reusable {
+            let active = self.active_chunks(&activity.input, boundary);
+            self.step_chunks(next, rule, boundary, Some((&active, &activity.output)));
+        } else {
+            self.step_chunks(next, rule, boundary, None);
+        }
+        activity.input.copy_from(self);
+        activity.output.copy_from(next);
+        activity.rule = Some(rule);
+        activity.boundary = Some(boundary);
+    }
+
+    fn copy_from(&mut self, other: &Board) {
+        self.width = other.width;
+        self.height = other.height;
+        self.words_per_row = other.words_per_row;
+        self.words.clone_from(&other.words);
+    }
+
+    /// Flags, band by band and word by word, the chunks whose next state may
+    /// differ from last time: any chunk that changed since `previous`, and
+    /// its neighbors as seen through `boundary`.
+    fn active_chunks(&self, previous: &Board, boundary: BoundaryMode) -> Vec<bool> {
+        let columns = self.words_per_row;
+        let bands = self.height.div_ceil(ROWS_PER_BAND);
+        let mut changed = vec![false; bands * columns];
+        for (y, (row, old)) in self
+            .words
+            .chunks(columns)
+            .zip(previous.words.chunks(columns))
+            .enumerate()
+        {
+            let band = &mut changed[y / ROWS_PER_BAND * columns..][..columns];
+            for (flag, (word, old)) in band.iter_mut().zip(row.iter().zip(old)) {
+                *flag |= word != old;
+            }
+        }
+
+        let mut active = vec![false; bands * columns];
+        for band in 0..bands {
+            for column in 0..columns {
+                if !changed[band * columns + column] {
+                    continue;
+                }
+                for db in -1..=1 {
+                    for dc in -1..=1 {
+                        let b = boundary.resolve(band as isize + db, bands);
+                        let c = boundary.resolve(column as isize + dc, columns);
+                        if let (Some(b), Some(c)) = (b, c) {
+                            active[b * columns + c] = true;
+                        }
+                    }
+                }
+            }
+        }
+        active
+    }
+
+    /// Steps the whole board, or with `reuse` only the chunks flagged active
+    /// while copying the rest from the given board.
+    fn step_chunks(
+        &self,
+        next: &mut Board,
+        rule: Rule,
+        boundary: BoundaryMode,
+        reuse: Option<(&[bool], &Board)>,
+    ) {
+        let words_per_row = self.words_per_row;
+        if words_per_row == 0 {
+            return;
+        }
+        let zeros = vec![0; words_per_row];
+        let mask = self.last_word_mask();
+
+        next.words
+            .par_chunks_mut(words_per_row * ROWS_PER_BAND)
+            .enumerate()
+            .for_each(|(band, out)| {
+                let first_row = band * ROWS_PER_BAND;
+                let active: Vec<usize> = match reuse {
+                    Some((flags, _)) => (0..words_per_row)
+                        .filter(|&i| flags[band * words_per_row + i])
+                        .collect(),
+                    None => (0..words_per_row).collect(),
+                };
+                if let Some((_, previous)) = reuse {
+                    let start = first_row * words_per_row;
+                    out.copy_from_slice(&previous.words[start..start + out.len()]);
+                }
+                if active.is_empty() {
+                    return;
+                }
+
+                let mut west = vec![0; words_per_row];
+                let mut east = vec![0; words_per_row];
+                for (r, out_row) in out.chunks_mut(words_per_row).enumerate() {
+                    let y = first_row + r;
+                    let neighbor_row = |dy: isize| {
+                        boundary
+                            .resolve(y as isize + dy, self.height)
+                            .map_or(&zeros[..], |y| self.row(y))
+                    };
+                    let (below, center, above) = (neighbor_row(-1), self.row(y), neighbor_row(1));
+
+                    // Bit-sliced counters: bit `x` of `sum[k]` is bit `k` of
+                    // cell `x`'s neighbor count.
+                    let mut sum = [
+                        vec![0u64; words_per_row],
+                        vec![0; words_per_row],
+                        vec![0; words_per_row],
+                        vec![0; words_per_row],
+                    ];
+                    let mut add = |plane: &[u64]| {
+                        for &i in &active {
+                            let mut carry = plane[i];
+                            for bits in sum.iter_mut() {
+                                let next_carry = bits[i] & carry;
+                                bits[i] ^= carry;
+                                carry = next_carry;
+                            }
+                        }
+                    };
+                    for (row, include_center) in [(below, true), (center, false), (above, true)] {
+                        self.shifted(row, boundary, &mut west, &mut east);
+                        add(&west);
+                        add(&east);
+                        if include_center {
+                            add(row);
+                        }
+                    }
+
+                    for &i in &active {
+                        let mut born = 0;
+                        let mut survives = 0;
+                        for n in 0..9 {
+                            let matches = (0..4).fold(u64::MAX, |acc, k| {
+                                acc & if n >> k & 1 == 1 {
+                                    sum[k][i]
+                                } else {
+                                    !sum[k][i]
+                                }
+                            });
+                            if rule.birth[n] {
+                                born |= matches;
+                            }
+                            if rule.survival[n] {
+                                survives |= matches;
+                            }
+                        }
+                        out_row[i] = center[i] & survives | !center[i] & born;
+                    }
+                    out_row[words_per_row - 1] &= mask;
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::neighborhood::Neighborhood;
+
+    /// One generation computed a cell at a time, as a reference.
+    fn naive_step(board: &Board, rule: Rule, boundary: BoundaryMode) -> Board {
+        let r = rule.range as isize;
+        let mut next = Board::new(board.width(), board.height());
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                let mut neighbors = 0;
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let outside = match rule.neighborhood {
+                            Neighborhood::Moore => false,
+                            Neighborhood::VonNeumann => dx.abs() + dy.abs() > r,
+                        };
+                        if outside || dx == 0 && dy == 0 {
+                            continue;
+                        }
+                        let nx = boundary.resolve(x as isize + dx, board.width());
+                        let ny = boundary.resolve(y as isize + dy, board.height());
+                        if let (Some(nx), Some(ny)) = (nx, ny) {
+                            neighbors += usize::from(board.get(nx, ny));
+                        }
+                    }
+                }
+                next.set(x, y, rule.next_state(board.get(x, y), neighbors));
+            }
+        }
+        next
+    }
+
+    fn cells(board: &Board) -> Vec<bool> {
+        (0..board.height())
+            .flat_map(|y| (0..board.width()).map(move |x| board.get(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn step_into_matches_naive_stepper() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let rules: [Rule; 3] = [
+            "B3/S23".parse().unwrap(),
+            "B36/S23".parse().unwrap(),
+            "B0/S8".parse().unwrap(),
+        ];
+        for (width, height) in [(1, 1), (3, 2), (63, 17), (64, 16), (65, 33), (130, 20)] {
+            for boundary in [
+                BoundaryMode::Torus,
+                BoundaryMode::DeadBorder,
+                BoundaryMode::Mirror,
+            ] {
+                for rule in rules {
+                    let mut board = Board::new(width, height);
+                    board.randomize(&mut rng, 0.4);
+                    let mut next = Board::new(width, height);
+                    board.step_into(&mut next, rule, boundary);
+                    let expected = naive_step(&board, rule, boundary);
+                    assert!(
+                        cells(&next) == cells(&expected),
+                        "{width}x{height} {boundary:?} {rule}"
+                    );
+                    assert_eq!(next.population(), expected.population());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn step_range_matches_naive_stepper() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let rules: [Rule; 4] = [
+            "B3/S23V".parse().unwrap(),
+            "R2,C0,M0,S3..6,B4..5,NM".parse().unwrap(),
+            "R3,C0,M1,S5..12,B6..9,NN".parse().unwrap(),
+            "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap(),
+        ];
+        for (width, height) in [(3, 4), (40, 30), (70, 9)] {
+            for boundary in [
+                BoundaryMode::Torus,
+                BoundaryMode::DeadBorder,
+                BoundaryMode::Mirror,
+            ] {
+                for rule in rules {
+                    let mut board = Board::new(width, height);
+                    board.randomize(&mut rng, 0.5);
+                    let mut next = Board::new(width, height);
+                    board.step_range(&mut next, rule, boundary);
+                    let expected = naive_step(&board, rule, boundary);
+                    assert!(
+                        cells(&next) == cells(&expected),
+                        "{width}x{height} {boundary:?} {rule}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn step_active_matches_naive_stepper() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let rules: [Rule; 2] = ["B3/S23".parse().unwrap(), "B36/S23".parse().unwrap()];
+        for (width, height) in [(5, 3), (64, 16), (130, 40), (200, 35)] {
+            for boundary in [
+                BoundaryMode::Torus,
+                BoundaryMode::DeadBorder,
+                BoundaryMode::Mirror,
+            ] {
+                // A soup in one corner, so most chunks stay empty.
+                let mut board = Board::new(width, height);
+                for y in 0..height.min(20) {
+                    for x in 0..width.min(20) {
+                        board.set(x, y, rng.gen_bool(0.4));
+                    }
+                }
+                let mut next = Board::new(width, height);
+                let mut activity = Activity::default();
+                for generation in 0..40 {
+                    let rule = rules[generation / 20];
+                    if generation % 7 == 3 {
+                        let (x, y) = (rng.gen_range(0..width), rng.gen_range(0..height));
+                        board.set(x, y, !board.get(x, y));
+                    }
+                    let expected = naive_step(&board, rule, boundary);
+                    board.step_active(&mut next, rule, boundary, &mut activity);
+                    assert!(
+                        cells(&next) == cells(&expected),
+                        "{width}x{height} {boundary:?} {rule} generation {generation}"
+                    );
+                    std::mem::swap(&mut board, &mut next);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn active_chunks_cover_changes_and_their_neighbors() {
+        // Four words across and four bands down.
+        let previous = Board::new(256, 64);
+        let active = |x, y, boundary| {
+            let mut board = previous.clone();
+            board.set(x, y, true);
+            let flags = board.active_chunks(&previous, boundary);
+            let mut chunks = Vec::new();
+            for band in 0..4 {
+                for column in 0..4 {
+                    if flags[band * 4 + column] {
+                        chunks.push((column, band));
+                    }
+                }
+            }
+            chunks
+        };
+        assert_eq!(
+            active(70, 20, BoundaryMode::DeadBorder),
+            [
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2)
+            ]
+        );
+        assert_eq!(
+            active(0, 0, BoundaryMode::DeadBorder),
+            [(0, 0), (1, 0), (0, 1), (1, 1)]
+        );
+        assert_eq!(
+            active(0, 0, BoundaryMode::Torus),
+            [
+                (0, 0),
+                (1, 0),
+                (3, 0),
+                (0, 1),
+                (1, 1),
+                (3, 1),
+                (0, 3),
+                (1, 3),
+                (3, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let mut board = Board::new(5, 5);
+        for x in 1..4 {
+            board.set(x, 2, true);
+        }
+        let mut next = Board::new(5, 5);
+        board.step_into(&mut next, Rule::default(), BoundaryMode::DeadBorder);
+        let vertical: Vec<_> = (1..4).map(|y| next.get(2, y)).collect();
+        assert_eq!(vertical, [true; 3]);
+        assert_eq!(next.population(), 3);
+    }
+}
@@ -0,0 +1,128 @@
+//! Fetching patterns from LifeWiki by name, behind the `pattern-browser`
+//! feature.
+//!
+//! F2 starts a search: type a pattern's name (for example "Gosper glider
+//! gun") and press Enter to download `conwaylife.com/patterns/<name>.rle`.
+//! Downloads are cached in `pattern_cache` next to the executable, and the
+//! pattern then follows the cursor like a paste until it is placed.
+
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{IoTaskPool, Task};
+
+use crate::bindings::{Action, Keybindings};
+use crate::pattern::Pattern;
+use crate::selection::PendingPaste;
+
+const PATTERN_URL: &str = "https://conwaylife.com/patterns";
+
+#[derive(Resource, Default)]
+pub struct PatternBrowser {
+    /// The name typed so far, while searching.
+    query: Option<String>,
+    download: Option<Task<Result<Pattern, String>>>,
+}
+
+/// LifeWiki's file name for a pattern: lowercase with everything but letters
+/// and digits removed.
+fn file_name(query: &str) -> String {
+    let name: String = query
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    format!("{name}.rle")
+}
+
+fn cache_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("pattern_cache")))
+        .unwrap_or_else(|| PathBuf::from("pattern_cache"))
+}
+
+/// Reads the pattern from the cache, downloading it first if needed.
+fn fetch(file_name: String) -> Result<Pattern, String> {
+    let path = cache_dir().join(&file_name);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => {
+            let url = format!("{PATTERN_URL}/{file_name}");
+            let text = ureq::get(&url)
+                .call()
+                .map_err(|err| format!("{url}: {err}"))?
+                .into_string()
+                .map_err(|err| err.to_string())?;
+            if let Err(err) = fs::create_dir_all(cache_dir()).and_then(|()| fs::write(&path, &text))
+            {
+                warn!("failed to cache {}: {err}", path.display());
+            }
+            text
+        }
+    };
+    Pattern::parse_rle(&text)
+}
+
+/// Handles typing while a search is open. Runs before everything else reads
+/// the keyboard and swallows key presses, so typing a name doesn't also
+/// trigger hotkeys.
+pub fn search_patterns(
+    mut browser: ResMut<PatternBrowser>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    bindings: Res<Keybindings>,
+    mut characters: EventReader<ReceivedCharacter>,
+) {
+    let Some(query) = &mut browser.query else {
+        characters.clear();
+        if bindings.just_pressed(Action::PatternSearch, &keyboard, &mouse) {
+            browser.query = Some(String::new());
+            info!("pattern search: type a name and press Enter");
+        }
+        return;
+    };
+
+    let mut changed = false;
+    for event in characters.iter() {
+        if !event.char.is_control() {
+            query.push(event.char);
+            changed = true;
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Back) {
+        changed |= query.pop().is_some();
+    }
+    if changed {
+        info!("pattern search: {query}");
+    }
+
+    if keyboard.just_pressed(KeyCode::Return) {
+        let name = file_name(query);
+        browser.query = None;
+        if name != ".rle" {
+            info!("fetching {name}");
+            browser.download = Some(IoTaskPool::get().spawn(async move { fetch(name) }));
+        }
+    } else if keyboard.just_pressed(KeyCode::Escape) {
+        browser.query = None;
+    }
+    keyboard.clear();
+}
+
+/// Hands a finished download over to be placed.
+pub fn receive_pattern(mut browser: ResMut<PatternBrowser>, mut pending: ResMut<PendingPaste>) {
+    let Some(task) = &mut browser.download else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    browser.download = None;
+    match result {
+        Ok(pattern) => pending.0 = Some(pattern),
+        Err(err) => error!("failed to fetch pattern: {err}"),
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::camera::CursorCell;
+use crate::config::Settings;
+use crate::topology::GridTopology;
+use crate::GameData;
+
+#[derive(Clone, Copy)]
+enum BrushShape {
+    /// A `(2r + 1)` square.
+    Square(isize),
+    /// Every cell within `r` of the center.
+    Circle(isize),
+}
+
+const BRUSHES: [BrushShape; 5] = [
+    BrushShape::Square(0),
+    BrushShape::Square(1),
+    BrushShape::Square(2),
+    BrushShape::Circle(2),
+    BrushShape::Circle(4),
+];
+
+/// The brush used for painting, picked from [`BRUSHES`] with `[` and `]`.
+#[derive(Resource, Default)]
+pub struct Brush {
+    index: usize,
+}
+
+impl Brush {
+    /// Cell offsets from the cursor that the brush covers.
+    pub fn offsets(&self) -> Vec<[isize; 2]> {
+        let (radius, circle) = match BRUSHES[self.index] {
+            BrushShape::Square(r) => (r, false),
+            BrushShape::Circle(r) => (r, true),
+        };
+        (-radius..=radius)
+            .flat_map(|dy| (-radius..=radius).map(move |dx| [dx, dy]))
+            .filter(|[dx, dy]| !circle || dx * dx + dy * dy <= radius * radius)
+            .collect()
+    }
+}
+
+pub fn cycle_brush(actions: Actions, mut brush: ResMut<Brush>) {
+    if actions.just_pressed(Action::BrushBigger) {
+        brush.index = (brush.index + 1) % BRUSHES.len();
+    } else if actions.just_pressed(Action::BrushSmaller) {
+        brush.index = (brush.index + BRUSHES.len() - 1) % BRUSHES.len();
+    }
+}
+
+/// Extra copies of every painted cell, reflected or rotated about the
+/// center of the board.
+#[derive(Resource, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Reflected across the vertical center line.
+    MirrorX,
+    /// Reflected across the horizontal center line.
+    MirrorY,
+    /// Reflected across both center lines.
+    FourFold,
+    /// Rotated 180 degrees.
+    Rotate2,
+    /// Rotated by each multiple of 90 degrees.
+    Rotate4,
+}
+
+impl Symmetry {
+    fn next(self) -> Self {
+        match self {
+            Symmetry::None => Symmetry::MirrorX,
+            Symmetry::MirrorX => Symmetry::MirrorY,
+            Symmetry::MirrorY => Symmetry::FourFold,
+            Symmetry::FourFold => Symmetry::Rotate2,
+            Symmetry::Rotate2 => Symmetry::Rotate4,
+            Symmetry::Rotate4 => Symmetry::None,
+        }
+    }
+
+    /// Every cell that painting `cell` should also paint, including `cell`
+    /// itself. Images that land off the board or between cells are dropped.
+    pub fn images(self, cell: [isize; 2], width: usize, height: usize) -> Vec<[isize; 2]> {
+        // Work in doubled coordinates relative to the board center so that
+        // centers between two cells stay integral.
+        let (w, h) = (width as isize, height as isize);
+        let (x, y) = (2 * cell[0] + 1 - w, 2 * cell[1] + 1 - h);
+        let doubled: Vec<[isize; 2]> = match self {
+            Symmetry::None => vec![[x, y]],
+            Symmetry::MirrorX => vec![[x, y], [-x, y]],
+            Symmetry::MirrorY => vec![[x, y], [x, -y]],
+            Symmetry::FourFold => vec![[x, y], [-x, y], [x, -y], [-x, -y]],
+            Symmetry::Rotate2 => vec![[x, y], [-x, -y]],
+            Symmetry::Rotate4 => vec![[x, y], [-y, x], [-x, -y], [y, -x]],
+        };
+        doubled
+            .into_iter()
+            .filter(|[x, y]| (x + w - 1) % 2 == 0 && (y + h - 1) % 2 == 0)
+            .map(|[x, y]| [(x + w - 1) / 2, (y + h - 1) / 2])
+            .filter(|&[x, y]| x >= 0 && y >= 0 && x < w && y < h)
+            .collect()
+    }
+}
+
+/// `M` cycles through the symmetry modes.
+pub fn cycle_symmetry(actions: Actions, mut symmetry: ResMut<Symmetry>) {
+    if actions.just_pressed(Action::CycleSymmetry) {
+        *symmetry = symmetry.next();
+        info!("symmetry: {:?}", *symmetry);
+    }
+}
+
+/// Parent of the translucent squares previewing the brush under the cursor.
+#[derive(Component)]
+pub struct BrushPreview;
+
+pub fn setup_brush_preview(mut commands: Commands) {
+    commands.spawn((SpatialBundle::default(), BrushPreview));
+}
+
+/// Follows the cursor with the preview, rebuilding it when the brush changes.
+pub fn update_brush_preview(
+    mut commands: Commands,
+    brush: Res<Brush>,
+    settings: Res<Settings>,
+    game_data: Res<GameData>,
+    topology: Res<GridTopology>,
+    cursor: CursorCell,
+    mut previews: Query<(Entity, &mut Transform, &mut Visibility), With<BrushPreview>>,
+) {
+    let Ok((preview, mut transform, mut visibility)) = previews.get_single_mut() else {
+        return;
+    };
+    let cell_size = settings.cell_size;
+    if brush.is_changed() {
+        commands.entity(preview).despawn_descendants();
+        commands.entity(preview).with_children(|parent| {
+            for [dx, dy] in brush.offsets() {
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(1.0, 1.0, 1.0, 0.25),
+                        custom_size: Some(Vec2::splat(cell_size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(
+                        dx as f32 * cell_size,
+                        dy as f32 * cell_size,
+                        0.0,
+                    ),
+                    ..Default::default()
+                });
+            }
+        });
+    }
+    match cursor.cell(&game_data.board) {
+        Some([x, y]) => {
+            transform.translation = topology.cell_center(x, y, cell_size).extend(3.0);
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
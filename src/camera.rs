@@ -0,0 +1,260 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::config::Settings;
+use crate::panel::BoardResized;
+use crate::topology::GridTopology;
+use crate::GameData;
+
+/// World units per logical pixel needed to fit the whole board in a window
+/// of `window_size`, leaving bars on the sides that don't match its aspect.
+pub fn fit_scale(window_size: Vec2, board_size: Vec2) -> f32 {
+    (board_size / window_size).max_element()
+}
+
+pub fn board_size(game_data: &GameData, settings: &Settings, topology: GridTopology) -> Vec2 {
+    topology.board_size(
+        game_data.board.width(),
+        game_data.board.height(),
+        settings.cell_size,
+    )
+}
+
+pub fn setup_camera(
+    mut commands: Commands,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+) {
+    let board_size = board_size(&game_data, &settings, *topology);
+
+    commands.spawn((
+        Camera2dBundle {
+            transform: Transform::from_translation((board_size / 2.0).extend(500.0)),
+            ..Default::default()
+        },
+        MainCamera,
+    ));
+}
+
+/// Rescales and recenters the camera so the board stays fully visible after
+/// the window or the board is resized.
+pub fn fit_camera_to_window(
+    mut resized: EventReader<WindowResized>,
+    mut board_resized: EventReader<BoardResized>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+) {
+    let window_size = match resized.iter().last() {
+        Some(event) => Vec2::new(event.width, event.height),
+        None if board_resized.iter().last().is_some() => {
+            let Ok(window) = windows.get_single() else {
+                return;
+            };
+            Vec2::new(window.width(), window.height())
+        }
+        None => return,
+    };
+    let board_size = board_size(&game_data, &settings, *topology);
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        projection.scale = fit_scale(window_size, board_size);
+        transform.translation = (board_size / 2.0).extend(transform.translation.z);
+    }
+}
+
+/// Marks the camera the board is viewed and edited through.
+#[derive(Component)]
+pub struct MainCamera;
+
+/// Maps the cursor through the main camera onto board cells.
+#[derive(SystemParam)]
+pub struct CursorCell<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<MainCamera>>,
+    settings: Res<'w, Settings>,
+    topology: Res<'w, GridTopology>,
+}
+
+impl CursorCell<'_, '_> {
+    /// World position under the cursor, if it is inside the window.
+    pub fn world_position(&self) -> Option<Vec2> {
+        let window = self.windows.get_single().ok()?;
+        self.world_at(window.cursor_position()?)
+    }
+
+    /// World position under a point in the window, with the origin at the
+    /// bottom left like [`Window::cursor_position`].
+    pub fn world_at(&self, viewport_position: Vec2) -> Option<Vec2> {
+        let (camera, transform) = self.cameras.get_single().ok()?;
+        let ray = camera.viewport_to_world(transform, viewport_position)?;
+        Some(ray.origin.truncate())
+    }
+
+    /// The world-space rectangle the camera shows.
+    pub fn visible_rect(&self) -> Option<Rect> {
+        let window = self.windows.get_single().ok()?;
+        let corner = self.world_at(Vec2::ZERO)?;
+        let opposite = self.world_at(Vec2::new(window.width(), window.height()))?;
+        Some(Rect::from_corners(corner, opposite))
+    }
+
+    /// The cell under the cursor, or `None` when the cursor is off the board.
+    pub fn cell(&self, board: &Board) -> Option<[usize; 2]> {
+        self.world_cell(board, self.world_position()?)
+    }
+
+    /// The cell under a point in the window.
+    pub fn cell_at(&self, board: &Board, viewport_position: Vec2) -> Option<[usize; 2]> {
+        self.world_cell(board, self.world_at(viewport_position)?)
+    }
+
+    fn world_cell(&self, board: &Board, world_position: Vec2) -> Option<[usize; 2]> {
+        self.topology
+            .world_to_cell(board, world_position, self.settings.cell_size)
+    }
+}
+
+const ZOOM_STEP: f32 = 0.1;
+
+/// Scroll to zoom around the cursor, drag with the middle button to pan.
+pub fn pan_and_zoom(
+    mut wheel: EventReader<MouseWheel>,
+    mut motion: EventReader<MouseMotion>,
+    actions: Actions,
+    cursor: CursorCell,
+    mut glide: ResMut<CameraGlide>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let anchor = cursor.world_position();
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+
+    let scroll: f32 = wheel
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 20.0,
+        })
+        .sum();
+    if scroll != 0.0 {
+        let old_scale = projection.scale;
+        projection.scale = (old_scale * (1.0 - ZOOM_STEP).powf(scroll)).clamp(0.02, 50.0);
+        // Keep the point under the cursor fixed while zooming.
+        if let Some(anchor) = anchor {
+            let camera = transform.translation.truncate();
+            let offset = (camera - anchor) * projection.scale / old_scale;
+            transform.translation = (anchor + offset).extend(transform.translation.z);
+        }
+        glide.zoomed(projection.scale);
+    }
+
+    let drag: Vec2 = motion.iter().map(|event| event.delta).sum();
+    if actions.pressed(Action::Pan) {
+        transform.translation.x -= drag.x * projection.scale;
+        transform.translation.y += drag.y * projection.scale;
+    }
+}
+
+/// How quickly the camera closes in on its target, per second.
+const GLIDE_RATE: f32 = 8.0;
+/// Room left around the live cells when fitting them, as a fraction.
+const FIT_MARGIN: f32 = 0.1;
+
+/// Where the camera is gliding to (center and scale), and whether it is
+/// tracking the live cells.
+#[derive(Resource, Default)]
+pub struct CameraGlide {
+    target: Option<(Vec2, f32)>,
+    following: bool,
+}
+
+impl CameraGlide {
+    /// Keeps the glide at `scale` after the user zooms, so following or a
+    /// fit in progress doesn't ease the zoom back.
+    pub fn zoomed(&mut self, scale: f32) {
+        if let Some((_, target_scale)) = &mut self.target {
+            *target_scale = scale;
+        }
+    }
+}
+
+/// `Z` fits the camera to every live cell and `L` toggles following their
+/// centroid.
+pub fn fit_and_follow_hotkeys(
+    actions: Actions,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut glide: ResMut<CameraGlide>,
+) {
+    if actions.just_pressed(Action::Follow) {
+        glide.following = !glide.following;
+        info!("follow: {}", if glide.following { "on" } else { "off" });
+    }
+    if !actions.just_pressed(Action::FitCamera) {
+        return;
+    }
+    let (Some((min, max)), Ok(window)) = (game_data.board.bounding_box(), windows.get_single())
+    else {
+        return;
+    };
+    let cell_size = settings.cell_size;
+    let half_cell = Vec2::splat(cell_size / 2.0);
+    // Hex rows are staggered, so any corner cell can be the outermost one.
+    let corners = [
+        [min[0], min[1]],
+        [max[0], min[1]],
+        [min[0], max[1]],
+        [max[0], max[1]],
+    ]
+    .map(|[x, y]| topology.cell_center(x, y, cell_size));
+    let low = corners.into_iter().reduce(Vec2::min).unwrap() - half_cell;
+    let high = corners.into_iter().reduce(Vec2::max).unwrap() + half_cell;
+    let window_size = Vec2::new(window.width(), window.height());
+    let scale = fit_scale(window_size, (high - low) * (1.0 + FIT_MARGIN));
+    glide.target = Some(((low + high) / 2.0, scale));
+}
+
+/// Moves the camera towards the fit target, or keeps it over the live cells
+/// while following.
+pub fn glide_camera(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+    time: Res<Time>,
+    mut glide: ResMut<CameraGlide>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    let current = (transform.translation.truncate(), projection.scale);
+    if glide.following {
+        if let Some([x, y]) = game_data.board.centroid() {
+            let center =
+                topology.cell_center(x.round() as usize, y.round() as usize, settings.cell_size);
+            let scale = glide.target.map_or(current.1, |(_, scale)| scale);
+            glide.target = Some((center, scale));
+        }
+    }
+    let Some((center, scale)) = glide.target else {
+        return;
+    };
+    let t = 1.0 - (-GLIDE_RATE * time.delta_seconds()).exp();
+    transform.translation = current.0.lerp(center, t).extend(transform.translation.z);
+    projection.scale += (scale - current.1) * t;
+    let settled = current.0.distance(center) < settings.cell_size * 0.01
+        && (scale - current.1).abs() < scale * 0.001;
+    if settled && !glide.following {
+        glide.target = None;
+    }
+}
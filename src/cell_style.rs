@@ -0,0 +1,82 @@
+//! What each cell sprite looks like in [`RenderMode::Sprites`]: a plain
+//! square, a circle, a rounded square, or an image of the user's choosing.
+//! The cell color tints whatever is chosen.
+//!
+//! [`RenderMode::Sprites`]: crate::config::RenderMode::Sprites
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::config::{CellShape, Settings};
+
+/// Side of the generated shape textures, in pixels.
+const SHAPE_RESOLUTION: usize = 32;
+/// Corner radius of [`CellShape::Rounded`], as a fraction of the side.
+const CORNER_RADIUS: f32 = 0.25;
+
+/// White with the shape cut out of the alpha channel, so the sprite color
+/// comes through unchanged. `coverage` gives how much of the pixel centered
+/// on `[x, y]` (in `-1.0..=1.0` across the cell) is inside the shape.
+fn shape_image(coverage: impl Fn(f32, f32) -> f32) -> Image {
+    let mut data = Vec::with_capacity(SHAPE_RESOLUTION * SHAPE_RESOLUTION * 4);
+    let pixel = 2.0 / SHAPE_RESOLUTION as f32;
+    for py in 0..SHAPE_RESOLUTION {
+        for px in 0..SHAPE_RESOLUTION {
+            let x = (px as f32 + 0.5) * pixel - 1.0;
+            let y = (py as f32 + 0.5) * pixel - 1.0;
+            let alpha = (coverage(x, y).clamp(0.0, 1.0) * 255.0) as u8;
+            data.extend_from_slice(&[255, 255, 255, alpha]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: SHAPE_RESOLUTION as u32,
+            height: SHAPE_RESOLUTION as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// Antialiased coverage of a pixel whose center is `distance` outside an
+/// edge, measured in the same units as the cell.
+fn edge_coverage(distance: f32) -> f32 {
+    0.5 - distance * SHAPE_RESOLUTION as f32 / 2.0
+}
+
+/// The texture for cell sprites. [`CellShape::Square`] uses the default
+/// white texture.
+pub fn cell_texture(
+    settings: &Settings,
+    images: &mut Assets<Image>,
+    asset_server: &AssetServer,
+) -> Handle<Image> {
+    match settings.cell_shape {
+        CellShape::Square => Handle::default(),
+        CellShape::Circle => images.add(shape_image(|x, y| {
+            edge_coverage(Vec2::new(x, y).length() - 1.0)
+        })),
+        CellShape::Rounded => {
+            let radius = CORNER_RADIUS * 2.0;
+            images.add(shape_image(|x, y| {
+                let inner = (Vec2::new(x, y).abs() - Vec2::splat(1.0 - radius)).max(Vec2::ZERO);
+                edge_coverage(inner.length() - radius)
+            }))
+        }
+        CellShape::Image => match &settings.cell_image {
+            Some(path) => asset_server.load(path.as_path()),
+            None => {
+                warn!("cell_shape is `image` but no cell_image is set");
+                Handle::default()
+            }
+        },
+    }
+}
+
+/// Side of a cell sprite, leaving a gap when [`Settings::cell_scale`] is
+/// below one.
+pub fn sprite_size(settings: &Settings) -> Vec2 {
+    Vec2::splat(settings.cell_size * settings.cell_scale.clamp(0.05, 1.0))
+}
@@ -0,0 +1,372 @@
+//! Batch soup searching: `--census <SOUPS>` runs that many random soups
+//! without a window, lets each one settle, and counts the objects left
+//! behind.
+//!
+//! Objects are named with apgcodes as used by Catagolue (`xs4_33` is a
+//! block, `xq4_153` a glider), so results can be compared with other
+//! searches.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::board::{Board, BoundaryMode};
+use crate::config::{Automaton, Settings};
+use crate::periodicity::{snapshot, Periodicity};
+use crate::rule::Rule;
+
+/// Side of the random square each soup starts from.
+const SOUP_SIZE: usize = 16;
+/// Soups still changing after this many generations count as unsettled.
+const MAX_GENERATIONS: u64 = 20_000;
+/// Longest population cycle treated as settled.
+const MAX_SETTLE_PERIOD: usize = 16;
+/// Generations an isolated object gets to repeat itself.
+const MAX_OBJECT_PERIOD: usize = 32;
+
+const KNOWN_OBJECTS: [(&str, &str); 11] = [
+    ("xs4_33", "block"),
+    ("xs6_696", "beehive"),
+    ("xs7_2596", "loaf"),
+    ("xs5_253", "boat"),
+    ("xs6_356", "ship"),
+    ("xs4_252", "tub"),
+    ("xs8_6996", "pond"),
+    ("xp2_7", "blinker"),
+    ("xp2_7e", "toad"),
+    ("xp2_318c", "beacon"),
+    ("xq4_153", "glider"),
+];
+
+#[derive(Serialize)]
+struct CensusEntry {
+    code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'static str>,
+    count: u64,
+}
+
+#[derive(Serialize)]
+struct CensusReport {
+    rule: String,
+    soups: u64,
+    settled: u64,
+    average_settle_generations: f64,
+    objects: Vec<CensusEntry>,
+}
+
+struct SoupResult {
+    settled_at: Option<u64>,
+    objects: HashMap<String, u64>,
+}
+
+/// Runs `soups` soups in parallel and prints or writes the census.
+pub fn run(settings: Settings, soups: u64) {
+    if settings.automaton != Automaton::Life || settings.topology.is_hex() {
+        eprintln!("the census only runs Life-like rules on square grids");
+        return;
+    }
+    let seed = settings.seed().unwrap_or_else(|| rand::thread_rng().gen());
+    let density = if settings.density > 0.0 {
+        settings.density
+    } else {
+        0.5
+    };
+    println!("running {soups} soups from seed {seed}");
+
+    let results: Vec<SoupResult> = (0..soups)
+        .into_par_iter()
+        .map(|soup| run_soup(&settings, seed.wrapping_add(soup), density))
+        .collect();
+
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for result in &results {
+        for (code, count) in &result.objects {
+            *counts.entry(code.clone()).or_default() += count;
+        }
+    }
+    let settle_times: Vec<u64> = results.iter().filter_map(|r| r.settled_at).collect();
+    let mut objects: Vec<CensusEntry> = counts
+        .into_iter()
+        .map(|(code, count)| CensusEntry {
+            name: KNOWN_OBJECTS
+                .iter()
+                .find(|(known, _)| *known == code)
+                .map(|&(_, name)| name),
+            code,
+            count,
+        })
+        .collect();
+    objects.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+
+    let report = CensusReport {
+        rule: settings.rule.to_string(),
+        soups,
+        settled: settle_times.len() as u64,
+        average_settle_generations: settle_times.iter().sum::<u64>() as f64
+            / settle_times.len().max(1) as f64,
+        objects,
+    };
+    println!(
+        "settled:     {}/{} (average {:.1} generations)",
+        report.settled, report.soups, report.average_settle_generations
+    );
+    for entry in report.objects.iter().take(10) {
+        println!(
+            "{:>10}  {} {}",
+            entry.count,
+            entry.code,
+            entry.name.unwrap_or("")
+        );
+    }
+    if let Some(path) = &settings.census_output {
+        match write_report(&report, path) {
+            Ok(()) => println!("wrote {}", path.display()),
+            Err(err) => eprintln!("failed to write {}: {err}", path.display()),
+        }
+    }
+}
+
+/// Writes CSV for a `.csv` path and JSON otherwise.
+fn write_report(report: &CensusReport, path: &Path) -> io::Result<()> {
+    let contents = if path.extension().is_some_and(|ext| ext == "csv") {
+        let mut csv = String::from("code,name,count\n");
+        for entry in &report.objects {
+            let name = entry.name.unwrap_or("");
+            csv.push_str(&format!("{},{name},{}\n", entry.code, entry.count));
+        }
+        csv
+    } else {
+        serde_json::to_string_pretty(report)?
+    };
+    fs::write(path, contents)
+}
+
+fn step(board: &Board, next: &mut Board, rule: Rule, boundary: BoundaryMode) {
+    if rule.is_classic() {
+        board.step_into(next, rule, boundary);
+    } else {
+        board.step_range(next, rule, boundary);
+    }
+}
+
+/// Runs one soup until its population has cycled through the same values a
+/// few times, then takes its census.
+fn run_soup(settings: &Settings, seed: u64, density: f64) -> SoupResult {
+    let (width, height) = (
+        settings.width.max(SOUP_SIZE),
+        settings.height.max(SOUP_SIZE),
+    );
+    let mut board = Board::new(width, height);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (left, bottom) = ((width - SOUP_SIZE) / 2, (height - SOUP_SIZE) / 2);
+    for y in bottom..bottom + SOUP_SIZE {
+        for x in left..left + SOUP_SIZE {
+            board.set(x, y, rng.gen_bool(density.clamp(0.0, 1.0)));
+        }
+    }
+
+    let mut next = Board::new(width, height);
+    let mut populations = vec![board.population()];
+    let mut settled_at = None;
+    for generation in 1..=MAX_GENERATIONS {
+        step(&board, &mut next, settings.rule, settings.boundary);
+        std::mem::swap(&mut board, &mut next);
+        populations.push(board.population());
+        if let Some(period) = population_period(&populations) {
+            settled_at = Some(generation - 3 * period as u64);
+            break;
+        }
+    }
+
+    let mut objects = HashMap::new();
+    if settled_at.is_some() {
+        for component in components(&board) {
+            if let Some(code) = apgcode(&component, settings.rule) {
+                *objects.entry(code).or_default() += 1;
+            }
+        }
+    }
+    SoupResult {
+        settled_at,
+        objects,
+    }
+}
+
+/// The shortest period whose last three cycles of population all match.
+fn population_period(populations: &[usize]) -> Option<usize> {
+    (1..=MAX_SETTLE_PERIOD).find(|&period| {
+        let window = (3 * period).max(12);
+        populations.len() > window + period
+            && (0..window).all(|i| {
+                let last = populations.len() - 1 - i;
+                populations[last] == populations[last - period]
+            })
+    })
+}
+
+/// Groups the live cells into 8-connected objects.
+fn components(board: &Board) -> Vec<Vec<(isize, isize)>> {
+    let mut seen = Board::new(board.width(), board.height());
+    let mut components = Vec::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if !board.get(x, y) || seen.get(x, y) {
+                continue;
+            }
+            seen.set(x, y, true);
+            let mut stack = vec![(x, y)];
+            let mut cells = Vec::new();
+            while let Some((cx, cy)) = stack.pop() {
+                cells.push((cx as isize, cy as isize));
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let Some([nx, ny]) = board.checked(cx as isize + dx, cy as isize + dy)
+                        else {
+                            continue;
+                        };
+                        if board.get(nx, ny) && !seen.get(nx, ny) {
+                            seen.set(nx, ny, true);
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+            components.push(cells);
+        }
+    }
+    components
+}
+
+/// Runs `cells` on their own until they repeat and names what they are.
+/// Objects that die or never repeat on their own are left out.
+fn apgcode(cells: &[(isize, isize)], rule: Rule) -> Option<String> {
+    let margin = MAX_OBJECT_PERIOD as isize;
+    let min_x = cells.iter().map(|&(x, _)| x).min()? - margin;
+    let min_y = cells.iter().map(|&(_, y)| y).min()? - margin;
+    let max_x = cells.iter().map(|&(x, _)| x).max()? + margin;
+    let max_y = cells.iter().map(|&(_, y)| y).max()? + margin;
+    let (width, height) = ((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+    let mut board = Board::new(width, height);
+    for &(x, y) in cells {
+        board.set((x - min_x) as usize, (y - min_y) as usize, true);
+    }
+
+    let mut next = Board::new(width, height);
+    let mut phases = Vec::new();
+    let mut history = Vec::new();
+    for generation in 0..=MAX_OBJECT_PERIOD {
+        let (hash, origin) = snapshot(&board)?;
+        if let Some(start) = history.iter().position(|&(seen, _)| seen == hash) {
+            let (_, (x0, y0)) = history[start];
+            let periodicity = Periodicity::from_repeat(
+                (generation - start) as u64,
+                origin.0 as isize - x0 as isize,
+                origin.1 as isize - y0 as isize,
+            );
+            let prefix = match periodicity {
+                Periodicity::StillLife => format!("xs{}", board.population()),
+                Periodicity::Oscillator { period } => format!("xp{period}"),
+                Periodicity::Spaceship { period, .. } => format!("xq{period}"),
+            };
+            let code = phases[start..]
+                .iter()
+                .flat_map(|phase: &Vec<(isize, isize)>| {
+                    (0..8).map(move |orientation| wechsler(phase, orientation))
+                })
+                .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+            return Some(format!("{prefix}_{code}"));
+        }
+        history.push((hash, origin));
+        phases.push(live_cells(&board));
+        step(&board, &mut next, rule, BoundaryMode::DeadBorder);
+        std::mem::swap(&mut board, &mut next);
+    }
+    None
+}
+
+fn live_cells(board: &Board) -> Vec<(isize, isize)> {
+    let mut cells = Vec::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if board.get(x, y) {
+                cells.push((x as isize, y as isize));
+            }
+        }
+    }
+    cells
+}
+
+/// Extended Wechsler format of `cells` in one of the eight orientations:
+/// strips five rows tall, one base-32 digit per column, with runs of zeros
+/// shortened to `w`, `x` and `y`.
+fn wechsler(cells: &[(isize, isize)], orientation: u8) -> String {
+    const DIGITS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+    let oriented: Vec<(isize, isize)> = cells
+        .iter()
+        .map(|&(x, y)| {
+            let (x, y) = if orientation & 4 != 0 { (y, x) } else { (x, y) };
+            let x = if orientation & 1 != 0 { -x } else { x };
+            let y = if orientation & 2 != 0 { -y } else { y };
+            (x, y)
+        })
+        .collect();
+    let min_x = oriented.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = oriented.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let width = oriented
+        .iter()
+        .map(|&(x, _)| x - min_x + 1)
+        .max()
+        .unwrap_or(0) as usize;
+    let height = oriented
+        .iter()
+        .map(|&(_, y)| y - min_y + 1)
+        .max()
+        .unwrap_or(0) as usize;
+
+    let mut columns = vec![vec![0u8; width]; height.div_ceil(5)];
+    for &(x, y) in &oriented {
+        let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+        columns[y / 5][x] |= 1 << (y % 5);
+    }
+
+    let strips: Vec<String> = columns
+        .iter()
+        .map(|strip| {
+            let mut code = String::new();
+            let mut zeros = 0;
+            let used = strip
+                .iter()
+                .rposition(|&column| column != 0)
+                .map_or(0, |i| i + 1);
+            for &column in &strip[..used] {
+                if column == 0 {
+                    zeros += 1;
+                    continue;
+                }
+                push_zeros(&mut code, zeros);
+                zeros = 0;
+                code.push(DIGITS[column as usize] as char);
+            }
+            code
+        })
+        .collect();
+    strips.join("z")
+}
+
+fn push_zeros(code: &mut String, mut zeros: usize) {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    while zeros >= 4 {
+        let run = zeros.min(39);
+        code.push('y');
+        code.push(DIGITS[run - 4] as char);
+        zeros -= run;
+    }
+    code.push_str(["", "0", "w", "x"][zeros]);
+}
@@ -0,0 +1,206 @@
+//! Immigration and QuadLife: Life rules played by two or four colors.
+//!
+//! The board steps with the configured rule as usual, and the colors of
+//! live cells live in [`GameData::colors`]. A newborn cell takes the color
+//! most of its live neighbors have; when no color has a majority and exactly
+//! one color is missing among them, as with three parents of different
+//! colors in QuadLife, it takes the missing one. Survivors keep their color,
+//! and cells set on the board by other tools take the first color.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::bindings::{Action, Actions};
+use crate::board::{Board, BoundaryMode};
+use crate::brush::{Brush, Symmetry};
+use crate::camera::CursorCell;
+use crate::config::Automaton;
+use crate::rule::Rule;
+use crate::topology::{self, GridTopology};
+use crate::GameData;
+
+const PALETTE: [Color; 4] = [
+    Color::rgb(1.0, 0.3, 0.2),
+    Color::rgb(0.2, 0.5, 1.0),
+    Color::rgb(0.3, 0.9, 0.3),
+    Color::rgb(1.0, 0.8, 0.2),
+];
+
+/// The color drawing paints.
+#[derive(Resource, Default)]
+pub struct ColorPalette(pub u8);
+
+/// How many colors `automaton` plays with, or `0` for uncolored ones.
+pub fn color_count(automaton: Automaton) -> u8 {
+    match automaton {
+        Automaton::Immigration => 2,
+        Automaton::QuadLife => 4,
+        _ => 0,
+    }
+}
+
+pub fn colored_mode(game_data: Res<GameData>) -> bool {
+    color_count(game_data.automaton) > 0
+}
+
+/// The color index of the live cell at `[x, y]`.
+pub fn cell_color_index(game_data: &GameData, x: usize, y: usize) -> u8 {
+    game_data
+        .colors
+        .get(y * game_data.board.width() + x)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// The color a live cell is drawn in.
+pub fn color(game_data: &GameData, x: usize, y: usize) -> Color {
+    PALETTE[cell_color_index(game_data, x, y) as usize % PALETTE.len()]
+}
+
+fn set_color(game_data: &mut GameData, x: usize, y: usize, color: u8) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    game_data.colors.resize(width * height, 0);
+    game_data.colors[y * width + x] = color;
+    game_data.board.set(x, y, true);
+}
+
+/// Gives every live cell a random color. Called after a new soup is
+/// generated with `rng`.
+pub fn randomize(game_data: &mut GameData, rng: &mut impl Rng) {
+    let count = color_count(game_data.automaton);
+    if count == 0 {
+        game_data.colors.clear();
+        return;
+    }
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    game_data.colors = vec![0; width * height];
+    for y in 0..height {
+        for x in (0..width).filter(|&x| game_data.board.get(x, y)) {
+            game_data.colors[y * width + x] = rng.gen_range(0..count);
+        }
+    }
+}
+
+/// The neighbor offsets births are counted over.
+fn neighbor_offsets(rule: Rule, topology: GridTopology) -> Vec<[isize; 2]> {
+    if topology.is_hex() {
+        return topology::HEX_NEIGHBORS.to_vec();
+    }
+    let range = rule.range as isize;
+    let mut offsets = Vec::new();
+    for dy in -range..=range {
+        let reach = rule.neighborhood.reach(rule.range, dy) as isize;
+        offsets.extend(
+            (-reach..=reach)
+                .filter(|&dx| (dx, dy) != (0, 0))
+                .map(|dx| [dx, dy]),
+        );
+    }
+    offsets
+}
+
+/// The color a cell born from parents with `counts` of each color takes.
+fn majority(counts: &[usize]) -> u8 {
+    let most = counts.iter().copied().max().unwrap_or(0);
+    let mut leaders = (0..counts.len()).filter(|&color| counts[color] == most);
+    let first = leaders.next().unwrap_or(0);
+    if leaders.next().is_none() {
+        return first as u8;
+    }
+    let mut missing = (0..counts.len()).filter(|&color| counts[color] == 0);
+    match (missing.next(), missing.next()) {
+        (Some(color), None) => color as u8,
+        _ => first as u8,
+    }
+}
+
+/// Colors the cells born this step after their parents and forgets the
+/// colors of cells that died. `previous` is the board before the step.
+/// Called after each step.
+pub fn update_colors(
+    game_data: &mut GameData,
+    previous: &Board,
+    boundary: BoundaryMode,
+    topology: GridTopology,
+) {
+    let count = color_count(game_data.automaton) as usize;
+    if count == 0 {
+        game_data.colors.clear();
+        return;
+    }
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    game_data.colors.resize(width * height, 0);
+    let offsets = neighbor_offsets(game_data.rule, topology);
+    let old = &game_data.colors;
+    let mut next = vec![0; width * height];
+    for y in 0..height {
+        for x in (0..width).filter(|&x| game_data.board.get(x, y)) {
+            next[y * width + x] = if previous.get(x, y) {
+                old[y * width + x]
+            } else {
+                let mut counts = vec![0; count];
+                for [dx, dy] in &offsets {
+                    let neighbor = (
+                        boundary.resolve(x as isize + dx, width),
+                        boundary.resolve(y as isize + dy, height),
+                    );
+                    if let (Some(x), Some(y)) = neighbor {
+                        if previous.get(x, y) {
+                            counts[old[y * width + x] as usize % count] += 1;
+                        }
+                    }
+                }
+                majority(&counts)
+            };
+        }
+    }
+    game_data.colors = next;
+}
+
+/// `1`–`4` pick the color to paint, up to the number of colors in play.
+pub fn select_color_palette(
+    actions: Actions,
+    game_data: Res<GameData>,
+    mut palette: ResMut<ColorPalette>,
+) {
+    let actions_by_color = [
+        Action::PaintColor1,
+        Action::PaintColor2,
+        Action::PaintColor3,
+        Action::PaintColor4,
+    ];
+    let count = color_count(game_data.automaton) as usize;
+    let Some(color) = actions_by_color[..count]
+        .iter()
+        .position(|&action| actions.just_pressed(action))
+    else {
+        return;
+    };
+    palette.0 = color as u8;
+    info!("painting color {}", color + 1);
+}
+
+/// The colored counterpart of `add_cells`: drawing brings cells to life in
+/// the palette color with the current brush.
+pub fn paint_colors(
+    mut game_data: ResMut<GameData>,
+    palette: Res<ColorPalette>,
+    brush: Res<Brush>,
+    symmetry: Res<Symmetry>,
+    cursor: CursorCell,
+    actions: Actions,
+) {
+    if !actions.pressed(Action::Draw) {
+        return;
+    }
+    let Some([x, y]) = cursor.cell(&game_data.board) else {
+        return;
+    };
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let color = palette.0 % color_count(game_data.automaton).max(1);
+    for [dx, dy] in brush.offsets() {
+        for [x, y] in symmetry.images([x as isize + dx, y as isize + dy], width, height) {
+            set_color(&mut game_data, x as usize, y as usize, color);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Split-screen comparison: the configured rule and each `--compare` rule
+//! run side by side from the same soup, stepped by one shared tick.
+//!
+//! Every board gets its own camera, viewport and render layer, so they can
+//! all sit at the world origin. Drawing toggles cells on the board under the
+//! cursor, `Space` pauses all of them and `R` starts a fresh shared soup.
+
+use std::time::Duration;
+
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+use rand::Rng;
+
+use crate::bindings::{Action, Actions};
+use crate::board::{Board, BoundaryMode};
+use crate::camera::fit_scale;
+use crate::config::{Automaton, Settings};
+use crate::rule::Rule;
+use crate::texture;
+use crate::topology::GridTopology;
+use crate::{GameData, SimulationTick};
+
+/// Logical pixels left between neighboring viewports.
+const GAP: f32 = 2.0;
+
+/// One of the boards being compared, at position `index` from the left.
+#[derive(Component)]
+struct Lane {
+    index: usize,
+    game_data: GameData,
+    next_board: Board,
+    image: Handle<Image>,
+}
+
+/// The camera showing the lane with the same `index`.
+#[derive(Component)]
+struct LaneCamera {
+    index: usize,
+}
+
+/// Boards built before the app starts, waiting to be spawned as lanes.
+#[derive(Resource)]
+struct NewLanes(Vec<GameData>);
+
+/// Opens a window with one board per rule in `rules`, all seeded alike.
+pub fn run(mut settings: Settings, rules: Vec<Rule>) {
+    let seed = settings.seed().unwrap_or_else(|| rand::thread_rng().gen());
+    settings.seed = Some(seed);
+    settings.automaton = Automaton::Life;
+    settings.topology = GridTopology::Square;
+    let lanes = rules
+        .into_iter()
+        .map(|rule| {
+            GameData::new(&Settings {
+                rule,
+                ..settings.clone()
+            })
+        })
+        .collect::<Vec<_>>();
+    let title = lanes
+        .iter()
+        .map(|game_data| game_data.rule.to_string())
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    App::new()
+        .insert_resource(ClearColor(settings.background_color()))
+        .insert_resource(SimulationTick {
+            timer: Timer::new(Duration::from_millis(settings.tick), TimerMode::Repeating),
+        })
+        .insert_resource(NewLanes(lanes))
+        .insert_resource(settings.keybindings.clone())
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: format!("Cellular Automata - {title}"),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .insert_resource(settings.boundary)
+        .insert_resource(settings.topology)
+        .insert_resource(settings)
+        .add_startup_system(spawn_lanes)
+        .add_system(fit_viewports)
+        .add_system(toggle_cells.after(fit_viewports))
+        .add_system(pause_and_reseed)
+        .add_system(step_lanes.after(pause_and_reseed))
+        .add_system(draw_lanes.after(step_lanes).after(toggle_cells))
+        .run();
+}
+
+/// Spawns a board sprite and a camera on a render layer of their own for
+/// each lane. Only the first camera clears the window, or each would wipe
+/// out the lanes drawn before it.
+fn spawn_lanes(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut new_lanes: ResMut<NewLanes>,
+    settings: Res<Settings>,
+) {
+    // Logged here rather than in `run`, before logging is set up.
+    if let Some(game_data) = new_lanes.0.first() {
+        info!("random soup seed: {}", game_data.seed);
+    }
+    for (index, game_data) in new_lanes.0.drain(..).enumerate() {
+        let (width, height) = (game_data.board.width(), game_data.board.height());
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: width as u32,
+                height: height as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        image.sampler_descriptor = ImageSampler::nearest();
+        let image = images.add(image);
+
+        let layer = RenderLayers::layer(index as u8 + 1);
+        let board_size = Vec2::new(width as f32, height as f32) * settings.cell_size;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(board_size),
+                    ..Default::default()
+                },
+                texture: image.clone(),
+                transform: Transform::from_translation((board_size / 2.0).extend(0.0)),
+                ..Default::default()
+            },
+            layer,
+            Lane {
+                index,
+                next_board: game_data.board.clone(),
+                game_data,
+                image,
+            },
+        ));
+        commands.spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    order: index as isize,
+                    ..Default::default()
+                },
+                camera_2d: Camera2d {
+                    clear_color: if index == 0 {
+                        ClearColorConfig::Default
+                    } else {
+                        ClearColorConfig::None
+                    },
+                },
+                transform: Transform::from_translation((board_size / 2.0).extend(500.0)),
+                ..Default::default()
+            },
+            layer,
+            LaneCamera { index },
+        ));
+    }
+}
+
+/// The logical `(left, width)` of lane `index` out of `count` across a
+/// window `window_width` wide.
+fn lane_span(index: usize, count: usize, window_width: f32) -> (f32, f32) {
+    let width = window_width / count as f32;
+    (width * index as f32, (width - GAP).max(1.0))
+}
+
+/// Splits the window into one column per lane and zooms each camera so its
+/// whole board fits in its column.
+fn fit_viewports(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    lanes: Query<&Lane>,
+    mut cameras: Query<(&LaneCamera, &mut Camera, &mut OrthographicProjection)>,
+    settings: Res<Settings>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let count = cameras.iter().count();
+    let scale_factor = window.scale_factor() as f32;
+    for (lane_camera, mut camera, mut projection) in &mut cameras {
+        let (left, width) = lane_span(lane_camera.index, count, window.width());
+        let viewport = Viewport {
+            physical_position: UVec2::new((left * scale_factor) as u32, 0),
+            physical_size: UVec2::new(
+                (width * scale_factor).max(1.0) as u32,
+                window.physical_height().max(1),
+            ),
+            ..Default::default()
+        };
+        if camera
+            .viewport
+            .as_ref()
+            .map(|old| (old.physical_position, old.physical_size))
+            != Some((viewport.physical_position, viewport.physical_size))
+        {
+            camera.viewport = Some(viewport);
+        }
+
+        let Some(lane) = lanes.iter().find(|lane| lane.index == lane_camera.index) else {
+            continue;
+        };
+        let board = &lane.game_data.board;
+        let board_size =
+            Vec2::new(board.width() as f32, board.height() as f32) * settings.cell_size;
+        let scale = fit_scale(Vec2::new(width, window.height()), board_size);
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+    }
+}
+
+/// Drawing toggles the cell under the cursor on whichever board's
+/// column the cursor is in.
+fn toggle_cells(
+    actions: Actions,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&LaneCamera, &Camera, &GlobalTransform)>,
+    mut lanes: Query<&mut Lane>,
+    settings: Res<Settings>,
+) {
+    if !actions.just_pressed(Action::Draw) {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let count = cameras.iter().count();
+    for (lane_camera, camera, transform) in &cameras {
+        let (left, width) = lane_span(lane_camera.index, count, window.width());
+        if cursor.x < left || cursor.x >= left + width {
+            continue;
+        }
+        let Some(ray) = camera.viewport_to_world(transform, cursor - Vec2::new(left, 0.0)) else {
+            return;
+        };
+        let cell = (ray.origin.truncate() / settings.cell_size).floor();
+        let Some(mut lane) = lanes
+            .iter_mut()
+            .find(|lane| lane.index == lane_camera.index)
+        else {
+            return;
+        };
+        let board = &mut lane.game_data.board;
+        if let Some([x, y]) = board.checked(cell.x as isize, cell.y as isize) {
+            let alive = board.get(x, y);
+            board.set(x, y, !alive);
+        }
+        return;
+    }
+}
+
+/// `Space` pauses every board and `R` gives them all the same new soup.
+fn pause_and_reseed(
+    actions: Actions,
+    mut sim_tick: ResMut<SimulationTick>,
+    mut lanes: Query<&mut Lane>,
+    settings: Res<Settings>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        sim_tick.toggle_pause();
+    }
+    if actions.just_pressed(Action::Reseed) {
+        let seed = rand::thread_rng().gen();
+        for mut lane in &mut lanes {
+            lane.game_data.reseed(seed, settings.density);
+        }
+        info!("random soup seed: {seed}");
+    }
+}
+
+fn step_lanes(
+    time: Res<Time>,
+    mut sim_tick: ResMut<SimulationTick>,
+    mut lanes: Query<&mut Lane>,
+    boundary: Res<BoundaryMode>,
+    topology: Res<GridTopology>,
+) {
+    if !sim_tick.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for mut lane in &mut lanes {
+        let Lane {
+            game_data,
+            next_board,
+            ..
+        } = &mut *lane;
+        game_data.step(next_board, *boundary, *topology);
+    }
+}
+
+/// Redraws each board's image, one pixel per cell, when it has changed.
+fn draw_lanes(lanes: Query<Ref<Lane>>, settings: Res<Settings>, mut images: ResMut<Assets<Image>>) {
+    let alive = texture::rgba_u8(settings.cell_color());
+    for lane in &lanes {
+        if !lane.is_changed() {
+            continue;
+        }
+        let Some(image) = images.get_mut(&lane.image) else {
+            continue;
+        };
+        let board = &lane.game_data.board;
+        for y in 0..board.height() {
+            let row = board.height() - 1 - y;
+            for x in 0..board.width() {
+                let offset = (row * board.width() + x) * 4;
+                let pixel = if board.get(x, y) { alive } else { [0; 4] };
+                image.data[offset..offset + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+}
@@ -0,0 +1,581 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::bindings::Keybindings;
+use crate::board::BoundaryMode;
+use crate::export::AnimationFormat;
+use crate::lenia::LeniaSettings;
+use crate::life3d::Life3dSettings;
+use crate::neighborhood::{Neighborhood, MAX_RANGE};
+use crate::rule::Rule;
+use crate::sonify::MusicalScale;
+use crate::stream::StreamFormat;
+use crate::theme::{self, Theme};
+use crate::topology::GridTopology;
+use crate::turmite::TurnRule;
+use crate::{GameData, SimulationTick};
+
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+/// How the board is drawn.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum RenderMode {
+    /// One sprite per cell.
+    #[default]
+    Sprites,
+    /// The whole board as a single image with one pixel per cell, which
+    /// scales to boards far too large for per-cell entities.
+    Texture,
+}
+
+/// The shape of each cell sprite in [`RenderMode::Sprites`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CellShape {
+    #[default]
+    Square,
+    Circle,
+    Rounded,
+    /// The PNG named by `cell_image`.
+    Image,
+}
+
+/// How finished frames are handed to the display.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum PresentationMode {
+    /// Wait for vertical sync, falling back when it isn't supported.
+    #[default]
+    AutoVsync,
+    /// Present as soon as possible, uncapped and possibly tearing.
+    Immediate,
+    /// Uncapped without tearing, replacing queued frames.
+    Mailbox,
+}
+
+impl From<PresentationMode> for bevy::window::PresentMode {
+    fn from(mode: PresentationMode) -> Self {
+        match mode {
+            PresentationMode::AutoVsync => Self::AutoVsync,
+            PresentationMode::Immediate => Self::Immediate,
+            PresentationMode::Mailbox => Self::Mailbox,
+        }
+    }
+}
+
+/// Where generations are computed.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    #[default]
+    Cpu,
+    /// A compute shader; the board is drawn straight from its output, and
+    /// only copied back for saving and the like while paused.
+    Gpu,
+}
+
+/// Which cellular automaton runs on the board.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Automaton {
+    /// Birth/survival rules such as Conway's Life, set with `rule`.
+    #[default]
+    Life,
+    /// `rule` played by two colors, newborn cells taking their parents'
+    /// majority color.
+    Immigration,
+    /// `rule` played by four colors, newborn cells taking their parents'
+    /// majority color.
+    QuadLife,
+    /// Conductors, electron heads and electron tails.
+    Wireworld,
+    /// Langton's Ant and its relatives, set with `ant_rule`.
+    Turmite,
+    /// One-dimensional rules drawn as a scrolling diagram, set with
+    /// `elementary_rule`.
+    Elementary,
+    /// Continuous values grown by a smooth kernel, set with `[lenia]`.
+    Lenia,
+    /// A rule defined by the Rhai script at `script`.
+    Script,
+}
+
+/// Command-line flags. Anything left unset falls back to the config file.
+#[derive(Parser)]
+#[command(about = "Conway's Game of Life")]
+pub struct Cli {
+    /// Config file to read defaults from and write settings back to
+    #[arg(long, default_value = DEFAULT_CONFIG_PATH)]
+    pub config: PathBuf,
+    /// Board width in cells
+    #[arg(long)]
+    pub width: Option<usize>,
+    /// Board height in cells
+    #[arg(long)]
+    pub height: Option<usize>,
+    /// Size of a cell on screen, in pixels
+    #[arg(long)]
+    pub cell_size: Option<f32>,
+    /// Milliseconds between generations
+    #[arg(long)]
+    pub tick: Option<u64>,
+    /// Probability that each cell starts alive
+    #[arg(long)]
+    pub density: Option<f64>,
+    /// Seed for the random initial fill
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Which automaton to run
+    #[arg(long, value_enum)]
+    pub automaton: Option<Automaton>,
+    /// Birth/survival rule, e.g. B3/S23
+    #[arg(long)]
+    pub rule: Option<Rule>,
+    /// Which surrounding cells the rule counts, overriding the rule string
+    #[arg(long, value_enum)]
+    pub neighborhood: Option<Neighborhood>,
+    /// How far the neighborhood reaches, from 1 to 10 cells
+    #[arg(long)]
+    pub range: Option<usize>,
+    /// Turmite turns per cell color, e.g. RL for Langton's Ant
+    #[arg(long)]
+    pub ant_rule: Option<TurnRule>,
+    /// Number of turmites to start with
+    #[arg(long)]
+    pub ants: Option<usize>,
+    /// Wolfram code of the elementary automaton, e.g. 30 or 110
+    #[arg(long)]
+    pub elementary_rule: Option<u8>,
+    /// Rhai script defining the rule for the script automaton
+    #[arg(long)]
+    pub script: Option<PathBuf>,
+    /// What cells at the edge of the board see as neighbors
+    #[arg(long, value_enum)]
+    pub boundary: Option<BoundaryMode>,
+    /// Square or hexagonal cells
+    #[arg(long, value_enum)]
+    pub topology: Option<GridTopology>,
+    /// How the board is drawn
+    #[arg(long, value_enum)]
+    pub render_mode: Option<RenderMode>,
+    /// Shape of cell sprites
+    #[arg(long, value_enum)]
+    pub cell_shape: Option<CellShape>,
+    /// Vsync, or an uncapped mode for running as fast as possible
+    #[arg(long, value_enum)]
+    pub present_mode: Option<PresentationMode>,
+    /// Where generations are computed
+    #[arg(long, value_enum)]
+    pub backend: Option<Backend>,
+    /// Color theme: classic, solarized, high-contrast, or one from the config
+    #[arg(long)]
+    pub theme: Option<String>,
+    /// Pattern file (.rle, .cells or .mc) to place in the middle of the board
+    #[arg(long)]
+    pub pattern: Option<PathBuf>,
+    /// Saved state to resume
+    #[arg(long)]
+    pub load: Option<PathBuf>,
+    /// Replay file to play back
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
+    /// Write generation, population, births, deaths and step time to this CSV
+    #[arg(long, value_name = "PATH")]
+    pub stats: Option<PathBuf>,
+    /// Run this many generations without a window, then print timings
+    #[arg(long, value_name = "GENERATIONS")]
+    pub headless: Option<u64>,
+    /// Run this many random soups without a window and count what they leave
+    #[arg(long, value_name = "SOUPS")]
+    pub census: Option<u64>,
+    /// Where to write the census; CSV for `.csv` paths, JSON otherwise
+    #[arg(long, value_name = "PATH")]
+    pub census_output: Option<PathBuf>,
+    /// Share the board with players who join this address, e.g. 0.0.0.0:9001
+    #[arg(long, value_name = "ADDR", conflicts_with = "join")]
+    pub host: Option<String>,
+    /// Edit the board of the game hosted at this address
+    #[arg(long, value_name = "ADDR")]
+    pub join: Option<String>,
+    /// Stream every generation to TCP clients at this address, e.g. 127.0.0.1:9002
+    #[arg(long, value_name = "ADDR")]
+    pub serve: Option<String>,
+    /// Frame encoding for `--serve`
+    #[arg(long, value_enum)]
+    pub serve_format: Option<StreamFormat>,
+    /// Run these rules side by side with `--rule`, from the same soup
+    #[arg(long, value_name = "RULE", num_args = 1..)]
+    pub compare: Vec<Rule>,
+    /// Run the experimental 3D mode instead, configured by `[life3d]`
+    #[arg(long = "3d")]
+    pub life3d: bool,
+    /// Pause once the board settles into a still life, oscillator, or spaceship
+    #[arg(long)]
+    pub auto_pause: bool,
+    /// Play births and deaths as notes
+    #[arg(long)]
+    pub audio: bool,
+    /// Start on the board straight away instead of the main menu
+    #[arg(long)]
+    pub no_menu: bool,
+}
+
+/// Effective configuration: the config file with command-line overrides
+/// applied on top. The fields marked `skip` only apply to the current run.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+    pub tick: u64,
+    pub density: f64,
+    pub automaton: Automaton,
+    #[serde(with = "rule_string")]
+    pub rule: Rule,
+    #[serde(with = "rule_string")]
+    pub ant_rule: TurnRule,
+    pub ants: usize,
+    pub elementary_rule: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<PathBuf>,
+    pub boundary: BoundaryMode,
+    pub topology: GridTopology,
+    pub render_mode: RenderMode,
+    pub cell_shape: CellShape,
+    /// Image drawn for each cell when `cell_shape` is `image`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell_image: Option<PathBuf>,
+    /// Cell sprite size relative to `cell_size`; below 1 leaves gaps
+    pub cell_scale: f32,
+    pub present_mode: PresentationMode,
+    pub backend: Backend,
+    /// Name of the color theme, which sets the two colors below
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Hex color of dead space, e.g. `000000`
+    pub background_color: String,
+    /// Hex color of live cells, e.g. `ffffff`
+    pub cell_color: String,
+    /// Fade cells through a gradient the longer they stay alive
+    pub color_by_age: bool,
+    /// File type F9 exports animations as
+    pub animation_format: AnimationFormat,
+    /// Frame encoding for the `--serve` board stream
+    pub serve_format: StreamFormat,
+    /// Generations captured per exported animation
+    pub animation_frames: usize,
+    /// Pixels per cell in exported animations and screenshots
+    pub animation_scale: usize,
+    /// Pause once the board settles into a still life, oscillator, or spaceship
+    pub auto_pause: bool,
+    /// Past generations kept for rewinding with the arrow keys
+    pub history_length: usize,
+    /// Play births and deaths as notes
+    pub audio: bool,
+    /// Loudness of the notes, from 0 to 1
+    pub audio_volume: f32,
+    /// Scale births are mapped onto
+    pub audio_scale: MusicalScale,
+    /// Open the main menu at startup
+    pub main_menu: bool,
+    /// Fixed seed for the random fill; a fresh one is picked when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// `--seed`, which only applies to this run and is never written back
+    #[serde(skip)]
+    pub cli_seed: Option<u64>,
+    /// Kept after the plain values, since TOML tables must come last.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub themes: Vec<Theme>,
+    pub keybindings: Keybindings,
+    pub lenia: LeniaSettings,
+    pub life3d: Life3dSettings,
+    #[serde(skip)]
+    pub run_3d: bool,
+    #[serde(skip)]
+    pub pattern: Option<PathBuf>,
+    #[serde(skip)]
+    pub load: Option<PathBuf>,
+    #[serde(skip)]
+    pub replay: Option<PathBuf>,
+    #[serde(skip)]
+    pub stats: Option<PathBuf>,
+    #[serde(skip)]
+    pub headless: Option<u64>,
+    #[serde(skip)]
+    pub census: Option<u64>,
+    #[serde(skip)]
+    pub census_output: Option<PathBuf>,
+    #[serde(skip)]
+    pub compare: Vec<Rule>,
+    #[serde(skip)]
+    pub serve: Option<String>,
+    #[serde(skip)]
+    pub host: Option<String>,
+    #[serde(skip)]
+    pub join: Option<String>,
+    /// Where the settings are written back on exit, if anywhere
+    #[serde(skip)]
+    pub config_path: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            width: 128,
+            height: 96,
+            cell_size: 5.0,
+            tick: 50,
+            density: 0.0,
+            automaton: Automaton::default(),
+            rule: Rule::default(),
+            ant_rule: TurnRule::default(),
+            ants: 1,
+            elementary_rule: 30,
+            script: None,
+            boundary: BoundaryMode::default(),
+            topology: GridTopology::default(),
+            render_mode: RenderMode::default(),
+            cell_shape: CellShape::default(),
+            cell_image: None,
+            cell_scale: 1.0,
+            present_mode: PresentationMode::default(),
+            backend: Backend::default(),
+            theme: None,
+            background_color: String::from("000000"),
+            cell_color: String::from("ffffff"),
+            color_by_age: false,
+            animation_format: AnimationFormat::default(),
+            serve_format: StreamFormat::default(),
+            animation_frames: 100,
+            animation_scale: 4,
+            auto_pause: false,
+            history_length: 1000,
+            audio: false,
+            audio_volume: 0.3,
+            audio_scale: MusicalScale::default(),
+            main_menu: true,
+            seed: None,
+            cli_seed: None,
+            themes: Vec::new(),
+            keybindings: Keybindings::default(),
+            lenia: LeniaSettings::default(),
+            life3d: Life3dSettings::default(),
+            run_3d: false,
+            pattern: None,
+            load: None,
+            replay: None,
+            stats: None,
+            headless: None,
+            census: None,
+            census_output: None,
+            compare: Vec::new(),
+            serve: None,
+            host: None,
+            join: None,
+            config_path: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the config file named on the command line (if it exists) and
+    /// applies the remaining flags on top of it.
+    pub fn load() -> Self {
+        let cli = Cli::parse();
+        let mut settings = match read_config(&cli.config) {
+            Ok(settings) => settings,
+            // There is no filesystem to read from on the web.
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::Unsupported
+                ) =>
+            {
+                Settings::default()
+            }
+            Err(err) => {
+                eprintln!("failed to read {}: {err}", cli.config.display());
+                Settings::default()
+            }
+        };
+        settings.config_path = Some(cli.config);
+        settings.width = cli.width.unwrap_or(settings.width);
+        settings.height = cli.height.unwrap_or(settings.height);
+        settings.cell_size = cli.cell_size.unwrap_or(settings.cell_size);
+        settings.tick = cli.tick.unwrap_or(settings.tick);
+        settings.density = cli.density.unwrap_or(settings.density);
+        settings.automaton = cli.automaton.unwrap_or(settings.automaton);
+        settings.rule = cli.rule.unwrap_or(settings.rule);
+        settings.rule.neighborhood = cli.neighborhood.unwrap_or(settings.rule.neighborhood);
+        settings.rule.range = cli.range.unwrap_or(settings.rule.range).clamp(1, MAX_RANGE);
+        settings.ant_rule = cli.ant_rule.unwrap_or(settings.ant_rule);
+        settings.ants = cli.ants.unwrap_or(settings.ants);
+        settings.elementary_rule = cli.elementary_rule.unwrap_or(settings.elementary_rule);
+        settings.script = cli.script.or(settings.script);
+        settings.boundary = cli.boundary.unwrap_or(settings.boundary);
+        settings.topology = cli.topology.unwrap_or(settings.topology);
+        settings.render_mode = cli.render_mode.unwrap_or(settings.render_mode);
+        settings.cell_shape = cli.cell_shape.unwrap_or(settings.cell_shape);
+        settings.present_mode = cli.present_mode.unwrap_or(settings.present_mode);
+        settings.backend = cli.backend.unwrap_or(settings.backend);
+        settings.cli_seed = cli.seed;
+        if let Some(name) = cli.theme.or_else(|| settings.theme.clone()) {
+            if !theme::apply_theme(&mut settings, &name) {
+                eprintln!("unknown theme `{name}`, keeping the configured colors");
+            }
+        }
+        settings.auto_pause |= cli.auto_pause;
+        settings.audio |= cli.audio;
+        settings.main_menu &= !cli.no_menu;
+        settings.pattern = cli.pattern;
+        settings.load = cli.load;
+        settings.replay = cli.replay;
+        settings.stats = cli.stats;
+        settings.headless = cli.headless;
+        settings.census = cli.census;
+        settings.census_output = cli.census_output;
+        settings.compare = cli.compare;
+        settings.serve = cli.serve;
+        settings.serve_format = cli.serve_format.unwrap_or(settings.serve_format);
+        settings.host = cli.host;
+        settings.join = cli.join;
+        if cfg!(not(feature = "multiplayer"))
+            && (settings.host.is_some() || settings.join.is_some())
+        {
+            eprintln!("--host and --join need the `multiplayer` feature");
+        }
+        settings.run_3d = cli.life3d;
+        if settings.backend == Backend::Gpu
+            && (settings.automaton != Automaton::Life
+                || settings.topology.is_hex()
+                || !settings.rule.is_classic()
+                || settings.rule.states > 2)
+        {
+            eprintln!(
+                "the GPU backend only runs two-state rules on eight neighbors, using the CPU"
+            );
+            settings.backend = Backend::Cpu;
+        }
+        settings
+    }
+
+    /// The seed for the random fill, `--seed` taking precedence over the
+    /// config file.
+    pub fn seed(&self) -> Option<u64> {
+        self.cli_seed.or(self.seed)
+    }
+
+    pub fn background_color(&self) -> Color {
+        Color::hex(&self.background_color).unwrap_or(Color::BLACK)
+    }
+
+    pub fn cell_color(&self) -> Color {
+        Color::hex(&self.cell_color).unwrap_or(Color::WHITE)
+    }
+}
+
+fn read_config(path: &Path) -> io::Result<Settings> {
+    toml::from_str(&fs::read_to_string(path)?)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn write_config(path: &Path, settings: &Settings) -> io::Result<()> {
+    let text = toml::to_string_pretty(settings)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(path, text)
+}
+
+/// The settings the app started with, command-line flags and all, so that
+/// what was changed while running can be told apart from them.
+#[derive(Resource)]
+pub struct LaunchSettings(pub Settings);
+
+/// Writes what was changed while running (the rules, boundary, tick and
+/// colors) back to the config file when the app exits. The rest of the file
+/// is left as it is, so one-off flags such as `--rule` never stick.
+pub fn save_config_on_exit(
+    mut exit: EventReader<AppExit>,
+    settings: Res<Settings>,
+    launch: Res<LaunchSettings>,
+    game_data: Res<GameData>,
+    boundary: Res<BoundaryMode>,
+    sim_tick: Res<SimulationTick>,
+) {
+    if exit.iter().next().is_none() || cfg!(target_arch = "wasm32") {
+        return;
+    }
+    let Some(path) = &settings.config_path else {
+        return;
+    };
+    let mut saved = match read_config(path) {
+        Ok(saved) => saved,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Settings::default(),
+        // Better to lose this run's changes than the file.
+        Err(err) => {
+            error!("not writing {}: {err}", path.display());
+            return;
+        }
+    };
+    let launch = &launch.0;
+    if game_data.rule != launch.rule {
+        saved.rule = game_data.rule;
+    }
+    if game_data.elementary.rule != launch.elementary_rule {
+        saved.elementary_rule = game_data.elementary.rule;
+    }
+    if *boundary != launch.boundary {
+        saved.boundary = *boundary;
+    }
+    let tick = sim_tick.timer.duration().as_millis() as u64;
+    if tick != launch.tick {
+        saved.tick = tick;
+    }
+    let colors = |settings: &Settings| {
+        (
+            settings.theme.clone(),
+            settings.background_color.clone(),
+            settings.cell_color.clone(),
+        )
+    };
+    if colors(&settings) != colors(launch) {
+        (saved.theme, saved.background_color, saved.cell_color) = colors(&settings);
+    }
+    if let Err(err) = write_config(path, &saved) {
+        error!("failed to write {}: {err}", path.display());
+    }
+}
+
+/// Stores rules in their usual string notation.
+pub mod rule_string {
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(
+        rule: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(rule)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
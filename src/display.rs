@@ -0,0 +1,36 @@
+//! Window presentation: fullscreen and the present mode, which decides
+//! whether frames wait for vsync.
+
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+
+use crate::bindings::{Action, Actions};
+use crate::config::Settings;
+
+/// `F11` switches between a window and borderless fullscreen.
+pub fn toggle_fullscreen(actions: Actions, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    if !actions.just_pressed(Action::Fullscreen) {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+        _ => WindowMode::Windowed,
+    };
+}
+
+/// Applies `present_mode` to the window whenever the setting changes.
+pub fn apply_present_mode(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let present_mode = PresentMode::from(settings.present_mode);
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+}
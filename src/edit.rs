@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::bindings::{Action, Actions};
+use crate::config::Settings;
+use crate::GameData;
+
+const DENSITY_STEP: f64 = 0.05;
+
+/// `,` and `.` lower and raise the fill density used by [`reseed_board`].
+pub fn adjust_density(actions: Actions, mut settings: ResMut<Settings>) {
+    let step = if actions.just_pressed(Action::DensityUp) {
+        DENSITY_STEP
+    } else if actions.just_pressed(Action::DensityDown) {
+        -DENSITY_STEP
+    } else {
+        return;
+    };
+    settings.density = (settings.density + step).clamp(0.0, 1.0);
+    info!("fill density: {:.2}", settings.density);
+}
+
+/// `R` fills the board with a new random soup at the current density.
+pub fn reseed_board(actions: Actions, settings: Res<Settings>, mut game_data: ResMut<GameData>) {
+    if actions.just_pressed(Action::Reseed) {
+        let seed = rand::thread_rng().gen();
+        game_data.reseed(seed, settings.density);
+        info!("random soup seed: {seed}");
+    }
+}
+
+/// `C` clears the board, `F` fills it and `I` inverts every cell. They
+/// don't fire with Ctrl held, so they don't clash with the clipboard
+/// shortcuts.
+pub fn board_hotkeys(actions: Actions, mut game_data: ResMut<GameData>) {
+    if actions.just_pressed(Action::Clear) {
+        game_data.board.fill(false);
+    } else if actions.just_pressed(Action::Fill) {
+        game_data.board.fill(true);
+    } else if actions.just_pressed(Action::Invert) {
+        game_data.board.invert();
+    }
+}
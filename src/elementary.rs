@@ -0,0 +1,100 @@
+//! Wolfram's elementary one-dimensional automata, such as Rule 30 and
+//! Rule 110, drawn as a space-time diagram.
+//!
+//! Each generation is one row of the board. The first starts at the top and
+//! every step writes the next one below it; once the bottom is reached the
+//! whole diagram scrolls up.
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::bindings::{Action, Actions};
+use crate::board::BoundaryMode;
+use crate::config::Automaton;
+use crate::GameData;
+
+pub struct Elementary {
+    /// The Wolfram code: bit `n` is the next state of a cell whose
+    /// left, center and right neighbors spell `n` in binary.
+    pub rule: u8,
+    /// Board row holding the newest generation.
+    row: usize,
+}
+
+impl Elementary {
+    pub fn new(rule: u8) -> Self {
+        Elementary { rule, row: 0 }
+    }
+}
+
+pub fn elementary_mode(game_data: Res<GameData>) -> bool {
+    game_data.automaton == Automaton::Elementary
+}
+
+/// Clears the board and starts a new diagram at the top: a single live cell
+/// in the middle, or a random row when `density` is above zero.
+pub fn restart(game_data: &mut GameData, density: f64) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    if height == 0 {
+        return;
+    }
+    let row = height - 1;
+    game_data.board.fill(false);
+    game_data.elementary.row = row;
+    if density > 0.0 {
+        let mut rng = ChaCha8Rng::seed_from_u64(game_data.seed);
+        for x in 0..width {
+            game_data.board.set(x, row, rng.gen_bool(density.min(1.0)));
+        }
+    } else if width > 0 {
+        game_data.board.set(width / 2, row, true);
+    }
+}
+
+/// Writes the next generation below the newest one.
+pub fn step(game_data: &mut GameData, boundary: BoundaryMode) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    if width == 0 || height == 0 {
+        return;
+    }
+    let board = &game_data.board;
+    let current = game_data.elementary.row.min(height - 1);
+    let cell = |x: isize| {
+        boundary
+            .resolve(x, width)
+            .is_some_and(|x| board.get(x, current))
+    };
+    let rule = game_data.elementary.rule;
+    let next: Vec<bool> = (0..width as isize)
+        .map(|x| {
+            let pattern = (cell(x - 1) as u8) << 2 | (cell(x) as u8) << 1 | cell(x + 1) as u8;
+            rule >> pattern & 1 == 1
+        })
+        .collect();
+
+    let row = if current == 0 {
+        game_data.board.scroll_up();
+        0
+    } else {
+        current - 1
+    };
+    for (x, alive) in next.into_iter().enumerate() {
+        game_data.board.set(x, row, alive);
+    }
+    game_data.elementary.row = row;
+}
+
+/// `Up` and `Down` step through the 256 rules.
+pub fn change_elementary_rule(actions: Actions, mut game_data: ResMut<GameData>) {
+    let rule = game_data.elementary.rule;
+    let rule = if actions.just_pressed(Action::NextElementaryRule) {
+        rule.wrapping_add(1)
+    } else if actions.just_pressed(Action::PreviousElementaryRule) {
+        rule.wrapping_sub(1)
+    } else {
+        return;
+    };
+    game_data.elementary.rule = rule;
+    info!("elementary rule {rule}");
+}
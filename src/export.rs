@@ -0,0 +1,251 @@
+//! Exporting the board as images next to the executable.
+//!
+//! F9 captures the next [`Settings::animation_frames`] generations as an
+//! animated GIF or APNG, and F12 saves a PNG of the current board. Both draw
+//! each cell as a square of [`Settings::animation_scale`] pixels rather than
+//! copying the window, so the output stays crisp.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::age;
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::config::Settings;
+use crate::texture;
+use crate::{GameData, SimulationTick};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnimationFormat {
+    #[default]
+    Gif,
+    Apng,
+}
+
+impl AnimationFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            AnimationFormat::Gif => "gif",
+            AnimationFormat::Apng => "png",
+        }
+    }
+}
+
+/// Boards captured so far while an export is running.
+#[derive(Resource, Default)]
+pub struct AnimationCapture {
+    frames: Option<Vec<Board>>,
+    last_generation: u64,
+}
+
+impl AnimationCapture {
+    /// Adds the board as a frame if a capture is running, the board is at a
+    /// new generation and the capture still needs frames.
+    pub fn record(&mut self, game_data: &GameData, settings: &Settings) {
+        if game_data.generation == self.last_generation {
+            return;
+        }
+        let Some(frames) = &mut self.frames else {
+            return;
+        };
+        if frames.len() < settings.animation_frames.max(1) {
+            frames.push(game_data.board.clone());
+            self.last_generation = game_data.generation;
+        }
+    }
+}
+
+/// F9 starts capturing. There is nowhere to save to in the browser.
+pub fn start_animation_capture(
+    actions: Actions,
+    game_data: Res<GameData>,
+    mut capture: ResMut<AnimationCapture>,
+) {
+    if actions.just_pressed(Action::RecordAnimation) && capture.frames.is_none() {
+        if cfg!(target_arch = "wasm32") {
+            warn!("animations can't be saved in the browser");
+            return;
+        }
+        capture.frames = Some(vec![game_data.board.clone()]);
+        capture.last_generation = game_data.generation;
+        info!("capturing an animation");
+    }
+}
+
+/// Adds a generation stepped outside the simulation tick, which records its
+/// own, and writes the file once enough frames have been captured.
+pub fn capture_animation_frames(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    sim_tick: Res<SimulationTick>,
+    mut capture: ResMut<AnimationCapture>,
+) {
+    capture.record(&game_data, &settings);
+    let Some(frames) = &capture.frames else {
+        return;
+    };
+    if frames.len() < settings.animation_frames.max(1) {
+        return;
+    }
+    let frames = capture.frames.take().unwrap_or_default();
+    let delay_millis = sim_tick.timer.duration().as_millis() as u16;
+    match write_animation(&frames, &settings, delay_millis) {
+        Ok(path) => info!("saved {}", path.display()),
+        Err(err) => error!("failed to save animation: {err}"),
+    }
+}
+
+/// F12 saves a screenshot of the board, except in the browser.
+pub fn save_screenshot(actions: Actions, game_data: Res<GameData>, settings: Res<Settings>) {
+    if !actions.just_pressed(Action::Screenshot) {
+        return;
+    }
+    if cfg!(target_arch = "wasm32") {
+        warn!("screenshots can't be saved in the browser");
+        return;
+    }
+    match write_screenshot(&game_data, &settings) {
+        Ok(path) => info!("saved {}", path.display()),
+        Err(err) => error!("failed to save screenshot: {err}"),
+    }
+}
+
+fn rgb(color: Color) -> [u8; 3] {
+    let [r, g, b, _] = texture::rgba_u8(color);
+    [r, g, b]
+}
+
+/// A fresh timestamped path next to the executable.
+fn export_path(extension: &str) -> io::Result<PathBuf> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis());
+    let name = format!("game_of_life_{stamp}.{extension}");
+    Ok(std::env::current_exe()?
+        .parent()
+        .map_or_else(|| PathBuf::from(&name), |dir| dir.join(&name)))
+}
+
+fn to_io<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::other(err)
+}
+
+fn write_screenshot(game_data: &GameData, settings: &Settings) -> io::Result<PathBuf> {
+    let scale = settings.animation_scale.max(1);
+    let board = &game_data.board;
+    let (width, height) = (board.width() * scale, board.height() * scale);
+    let background = rgb(settings.background_color());
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for py in 0..height {
+        let y = board.height() - 1 - py / scale;
+        for px in 0..width {
+            let color = age::cell_color(game_data, settings, px / scale, y);
+            pixels.extend_from_slice(&color.map_or(background, rgb));
+        }
+    }
+
+    let path = export_path("png")?;
+    let file = BufWriter::new(File::create(&path)?);
+    let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(to_io)?;
+    writer.write_image_data(&pixels).map_err(to_io)?;
+    writer.finish().map_err(to_io)?;
+    Ok(path)
+}
+
+/// The `[r, g, b]` pixels of `board` scaled up by `scale`, top row first.
+fn render_rgb(board: &Board, scale: usize, dead: [u8; 3], alive: [u8; 3]) -> Vec<u8> {
+    let (width, height) = (board.width() * scale, board.height() * scale);
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for py in 0..height {
+        let y = board.height() - 1 - py / scale;
+        for px in 0..width {
+            let color = if board.get(px / scale, y) {
+                alive
+            } else {
+                dead
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+    pixels
+}
+
+fn write_animation(
+    frames: &[Board],
+    settings: &Settings,
+    delay_millis: u16,
+) -> io::Result<PathBuf> {
+    let scale = settings.animation_scale.max(1);
+    let (dead, alive) = (rgb(settings.background_color()), rgb(settings.cell_color()));
+    let (width, height) = frames.first().map_or((0, 0), |board| {
+        (board.width() * scale, board.height() * scale)
+    });
+
+    let format = settings.animation_format;
+    let too_large = width > usize::from(u16::MAX) || height > usize::from(u16::MAX);
+    if format == AnimationFormat::Gif && too_large {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{width}x{height} is too large for a GIF, try a smaller animation_scale"),
+        ));
+    }
+    let path = export_path(format.extension())?;
+    let file = BufWriter::new(File::create(&path)?);
+
+    match format {
+        AnimationFormat::Gif => {
+            let palette = [dead, alive].concat();
+            let mut encoder =
+                gif::Encoder::new(file, width as u16, height as u16, &palette).map_err(to_io)?;
+            encoder.set_repeat(gif::Repeat::Infinite).map_err(to_io)?;
+            for board in frames {
+                let indices: Vec<u8> = render_rgb(board, scale, dead, alive)
+                    .chunks(3)
+                    .map(|pixel| u8::from(pixel == alive))
+                    .collect();
+                let frame = gif::Frame {
+                    width: width as u16,
+                    height: height as u16,
+                    // GIF delays are in hundredths of a second.
+                    delay: (delay_millis / 10).max(1),
+                    buffer: Cow::Owned(indices),
+                    ..Default::default()
+                };
+                encoder.write_frame(&frame).map_err(to_io)?;
+            }
+        }
+        AnimationFormat::Apng => {
+            let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder
+                .set_animated(frames.len() as u32, 0)
+                .map_err(to_io)?;
+            encoder
+                .set_frame_delay(delay_millis.max(1), 1000)
+                .map_err(to_io)?;
+            let mut writer = encoder.write_header().map_err(to_io)?;
+            for board in frames {
+                writer
+                    .write_image_data(&render_rgb(board, scale, dead, alive))
+                    .map_err(to_io)?;
+            }
+            writer.finish().map_err(to_io)?;
+        }
+    }
+    Ok(path)
+}
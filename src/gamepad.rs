@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+
+use crate::config::Settings;
+use crate::topology::GridTopology;
+use crate::{GameData, SimulationTick};
+
+/// Cells per second the cursor moves with the stick fully tilted.
+const CURSOR_SPEED: f32 = 40.0;
+
+/// A board cursor driven by the left stick, shown once a gamepad connects.
+#[derive(Resource, Default)]
+pub struct GamepadCursor {
+    /// Position in cells; the cell under it is the floor of each axis.
+    position: Vec2,
+}
+
+#[derive(Component)]
+pub struct GamepadCursorMarker;
+
+pub fn setup_gamepad_cursor(
+    mut commands: Commands,
+    mut cursor: ResMut<GamepadCursor>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+) {
+    cursor.position = Vec2::new(
+        game_data.board.width() as f32,
+        game_data.board.height() as f32,
+    ) / 2.0;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1.0, 0.4, 0.4, 0.6),
+                custom_size: Some(Vec2::splat(settings.cell_size)),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        GamepadCursorMarker,
+    ));
+}
+
+/// Left stick moves the cursor, Start pauses and the triggers halve or
+/// double the simulation speed.
+#[allow(clippy::too_many_arguments)]
+pub fn gamepad_input(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+    game_data: Res<GameData>,
+    mut cursor: ResMut<GamepadCursor>,
+    mut sim_tick: ResMut<SimulationTick>,
+    mut markers: Query<(&mut Transform, &mut Visibility), With<GamepadCursorMarker>>,
+) {
+    let Some(gamepad) = gamepads.iter().next() else {
+        for (_, mut visibility) in markers.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+    let button = |button_type| GamepadButton::new(gamepad, button_type);
+
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let stick = Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    );
+    cursor.position = (cursor.position + stick * CURSOR_SPEED * time.delta_seconds()).clamp(
+        Vec2::ZERO,
+        Vec2::new(width as f32 - 0.01, height as f32 - 0.01),
+    );
+    let (x, y) = (cursor.position.x as usize, cursor.position.y as usize);
+
+    if buttons.just_pressed(button(GamepadButtonType::Start)) {
+        sim_tick.toggle_pause();
+    }
+    if buttons.just_pressed(button(GamepadButtonType::RightTrigger2)) {
+        sim_tick.scale_interval(0.5);
+    } else if buttons.just_pressed(button(GamepadButtonType::LeftTrigger2)) {
+        sim_tick.scale_interval(2.0);
+    }
+
+    let cell_size = settings.cell_size;
+    for (mut transform, mut visibility) in markers.iter_mut() {
+        transform.translation = topology.cell_center(x, y, cell_size).extend(3.0);
+        *visibility = Visibility::Visible;
+    }
+}
+
+/// South (A) paints the cell under the cursor and East (B) erases it.
+pub fn gamepad_paint(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    cursor: Res<GamepadCursor>,
+    mut game_data: ResMut<GameData>,
+) {
+    let Some(gamepad) = gamepads.iter().next() else {
+        return;
+    };
+    let button = |button_type| GamepadButton::new(gamepad, button_type);
+    let (x, y) = (cursor.position.x as usize, cursor.position.y as usize);
+    if buttons.pressed(button(GamepadButtonType::South)) {
+        game_data.board.set(x, y, true);
+    } else if buttons.pressed(button(GamepadButtonType::East)) {
+        game_data.board.set(x, y, false);
+    }
+}
@@ -0,0 +1,60 @@
+//! Multi-state "Generations" rules such as Brian's Brain (`/2/3`).
+//!
+//! The board only holds live cells. A live cell that fails to survive
+//! becomes dying instead of dead and passes through the remaining states, one
+//! per generation, before it is dead again. Dying cells do not count as
+//! neighbors and cannot be born into.
+
+use bevy::prelude::*;
+
+use crate::board::Board;
+use crate::config::Settings;
+use crate::GameData;
+
+/// How far through dying the cell at `[x, y]` is: `0` for live and dead
+/// cells, then `1` up to `states - 2`.
+pub fn dying_state(game_data: &GameData, x: usize, y: usize) -> u8 {
+    game_data
+        .decay
+        .get(y * game_data.board.width() + x)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Moves dying cells one state on and starts decay for live cells that did
+/// not survive. `previous` is the board before the step. Called after each
+/// step.
+pub fn update_decay(game_data: &mut GameData, previous: &Board) {
+    let states = game_data.rule.states;
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    if states <= 2 {
+        game_data.decay.clear();
+        return;
+    }
+    game_data.decay.resize(width * height, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let decay = &mut game_data.decay[y * width + x];
+            if previous.get(x, y) {
+                *decay = u8::from(!game_data.board.get(x, y));
+            } else if *decay > 0 {
+                game_data.board.set(x, y, false);
+                *decay = if *decay + 2 < states { *decay + 1 } else { 0 };
+            }
+        }
+    }
+}
+
+/// The color of a dying cell, fading from the cell color towards the
+/// background as it nears death, or `None` if the cell is not dying.
+pub fn dying_color(game_data: &GameData, settings: &Settings, x: usize, y: usize) -> Option<Color> {
+    let state = dying_state(game_data, x, y);
+    if state == 0 {
+        return None;
+    }
+    let t = state as f32 / (game_data.rule.states - 1) as f32;
+    let from = Vec4::from(settings.cell_color().as_rgba_f32());
+    let to = Vec4::from(settings.background_color().as_rgba_f32());
+    let rgba = from.lerp(to, t);
+    Some(Color::rgba(rgba.x, rgba.y, rgba.z, rgba.w))
+}
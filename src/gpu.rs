@@ -0,0 +1,474 @@
+//! Optional backend that runs the Life rule in a compute shader.
+//!
+//! The board lives in two storage textures. Each generation reads one and
+//! writes the other; after an odd number of generations the result is copied
+//! back so the displayed texture is always the current one. The CPU copy in
+//! [`GameData`] is only refreshed by reading the texture back when the
+//! simulation is paused, and any edit to it is uploaded again, so the board
+//! should be edited while paused. Everything else that reads the board, such
+//! as saving, sees it as of the last pause while the simulation runs.
+
+use std::borrow::Cow;
+use std::num::NonZeroU32;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{self, RenderGraph};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::{RenderApp, RenderSet};
+
+use crate::board::BoundaryMode;
+use crate::config::{Backend, Settings};
+use crate::rule::MAX_NEIGHBORS;
+use crate::{GameData, SimulationTick};
+
+const WORKGROUP_SIZE: u32 = 8;
+/// wgpu requires buffer rows in texture copies to be aligned to this.
+const COPY_ROW_ALIGNMENT: u32 = 256;
+
+pub fn gpu_backend(settings: Res<Settings>) -> bool {
+    settings.backend == Backend::Gpu
+}
+
+pub struct GpuLifePlugin;
+
+impl Plugin for GpuLifePlugin {
+    fn build(&self, app: &mut App) {
+        let shader = app
+            .world
+            .resource_mut::<Assets<Shader>>()
+            .add(Shader::from_wgsl(include_str!("gpu_life.wgsl")));
+        let (sender, receiver) = channel();
+
+        app.insert_resource(ReadbackReceiver(Mutex::new(receiver)))
+            .init_resource::<GpuStepRequest>()
+            .add_plugin(ExtractResourcePlugin::<GpuBoardImages>::default())
+            .add_plugin(ExtractResourcePlugin::<GpuStepRequest>::default())
+            .add_startup_system(setup_gpu_board.run_if(gpu_backend))
+            .add_systems(
+                (apply_readback, upload_board, request_steps)
+                    .chain()
+                    .after(crate::tick_simulation)
+                    .distributive_run_if(gpu_backend),
+            );
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .insert_resource(LifeShader(shader))
+            .insert_resource(ReadbackSender(Mutex::new(sender)))
+            .init_resource::<LifePipeline>()
+            .add_system(queue_bind_groups.in_set(RenderSet::Queue))
+            .add_system(map_readback.in_set(RenderSet::Cleanup));
+
+        let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
+        render_graph.add_node("game_of_life", LifeNode);
+        render_graph.add_node_edge(
+            "game_of_life",
+            bevy::render::main_graph::node::CAMERA_DRIVER,
+        );
+    }
+}
+
+/// The two textures generations alternate between. `front` is displayed and
+/// always holds the current generation at the end of a frame.
+#[derive(Resource, Clone, ExtractResource)]
+struct GpuBoardImages {
+    front: Handle<Image>,
+    back: Handle<Image>,
+}
+
+/// What the compute node should do this frame, rebuilt every frame.
+#[derive(Resource, Clone, Default, ExtractResource)]
+struct GpuStepRequest {
+    steps: u32,
+    readback: bool,
+    size: [u32; 2],
+    params: [u8; 32],
+}
+
+#[derive(Resource)]
+struct ReadbackReceiver(Mutex<Receiver<Vec<u8>>>);
+
+#[derive(Resource)]
+struct ReadbackSender(Mutex<Sender<Vec<u8>>>);
+
+fn board_image(size: [u32; 2]) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size[0],
+            height: size[1],
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8Unorm,
+    );
+    image.texture_descriptor.usage = TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::STORAGE_BINDING
+        | TextureUsages::TEXTURE_BINDING;
+    image.sampler_descriptor = bevy::render::texture::ImageSampler::nearest();
+    image
+}
+
+fn setup_gpu_board(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+) {
+    let size = [
+        game_data.board.width() as u32,
+        game_data.board.height() as u32,
+    ];
+    let front = images.add(board_image(size));
+    let back = images.add(board_image(size));
+
+    let board_size = Vec2::new(size[0] as f32, size[1] as f32) * settings.cell_size;
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            custom_size: Some(board_size),
+            ..Default::default()
+        },
+        texture: front.clone(),
+        transform: Transform::from_translation((board_size / 2.0).extend(0.0)),
+        ..Default::default()
+    });
+    commands.insert_resource(GpuBoardImages { front, back });
+}
+
+/// Copies the CPU board into the front texture whenever it was edited.
+fn upload_board(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    board_images: Res<GpuBoardImages>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !game_data.is_changed() {
+        return;
+    }
+    let Some(image) = images.get_mut(&board_images.front) else {
+        return;
+    };
+    let board = &game_data.board;
+    let alive = settings
+        .cell_color()
+        .as_linear_rgba_f32()
+        .map(|c| (c * 255.0) as u8);
+    for y in 0..board.height() {
+        let row = board.height() - 1 - y;
+        for x in 0..board.width() {
+            let offset = (row * board.width() + x) * 4;
+            let pixel = if board.get(x, y) { alive } else { [0; 4] };
+            image.data[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+}
+
+/// Writes a read-back texture into the CPU board.
+fn apply_readback(receiver: Res<ReadbackReceiver>, mut game_data: ResMut<GameData>) {
+    let Ok(pixels) = receiver.0.lock().unwrap().try_recv() else {
+        return;
+    };
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    for y in 0..height {
+        let row = height - 1 - y;
+        for x in 0..width {
+            let alpha = pixels[(row * width + x) * 4 + 3];
+            game_data.board.set(x, y, alpha > 127);
+        }
+    }
+}
+
+fn request_steps(
+    mut game_data: ResMut<GameData>,
+    mut request: ResMut<GpuStepRequest>,
+    sim_tick: Res<SimulationTick>,
+    mut was_paused: Local<bool>,
+    boundary: Res<BoundaryMode>,
+    settings: Res<Settings>,
+) {
+    let steps = sim_tick.timer.times_finished_this_tick();
+    let paused = sim_tick.timer.paused();
+    // Generation counting must not look like an edit to `upload_board`.
+    game_data.bypass_change_detection().generation += steps as u64;
+
+    let mask = |counts: [bool; MAX_NEIGHBORS + 1]| {
+        (0..9)
+            .filter(|&n| counts[n])
+            .fold(0u32, |mask, n| mask | 1 << n)
+    };
+    let boundary = match *boundary {
+        BoundaryMode::Torus => 0u32,
+        BoundaryMode::DeadBorder => 1,
+        BoundaryMode::Mirror => 2,
+    };
+    let mut params = [0u8; 32];
+    for (i, c) in settings
+        .cell_color()
+        .as_linear_rgba_f32()
+        .iter()
+        .enumerate()
+    {
+        params[i * 4..i * 4 + 4].copy_from_slice(&c.to_le_bytes());
+    }
+    params[16..20].copy_from_slice(&mask(game_data.rule.birth).to_le_bytes());
+    params[20..24].copy_from_slice(&mask(game_data.rule.survival).to_le_bytes());
+    params[24..28].copy_from_slice(&boundary.to_le_bytes());
+
+    *request = GpuStepRequest {
+        steps,
+        readback: paused && !*was_paused,
+        size: [
+            game_data.board.width() as u32,
+            game_data.board.height() as u32,
+        ],
+        params,
+    };
+    *was_paused = paused;
+}
+
+#[derive(Resource)]
+struct LifeShader(Handle<Shader>);
+
+#[derive(Resource)]
+struct LifePipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for LifePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout =
+            world
+                .resource::<RenderDevice>()
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: Some("game_of_life_layout"),
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::Texture {
+                                sample_type: TextureSampleType::Float { filterable: false },
+                                view_dimension: TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::StorageTexture {
+                                access: StorageTextureAccess::WriteOnly,
+                                format: TextureFormat::Rgba8Unorm,
+                                view_dimension: TextureViewDimension::D2,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ],
+                });
+        let shader = world.resource::<LifeShader>().0.clone();
+        let pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some(Cow::from("game_of_life_update")),
+                    layout: vec![layout.clone()],
+                    push_constant_ranges: Vec::new(),
+                    shader,
+                    shader_defs: Vec::new(),
+                    entry_point: Cow::from("update"),
+                });
+        LifePipeline { layout, pipeline }
+    }
+}
+
+/// `groups[0]` steps front -> back, `groups[1]` steps back -> front.
+#[derive(Resource)]
+struct LifeBindGroups {
+    groups: [BindGroup; 2],
+}
+
+#[derive(Resource)]
+struct ReadbackBuffer {
+    buffer: Buffer,
+    padded_row_bytes: u32,
+}
+
+fn queue_bind_groups(
+    mut commands: Commands,
+    pipeline: Res<LifePipeline>,
+    gpu_images: Res<RenderAssets<Image>>,
+    board_images: Option<Res<GpuBoardImages>>,
+    request: Option<Res<GpuStepRequest>>,
+    render_device: Res<RenderDevice>,
+) {
+    let (Some(board_images), Some(request)) = (board_images, request) else {
+        return;
+    };
+    let (Some(front), Some(back)) = (
+        gpu_images.get(&board_images.front),
+        gpu_images.get(&board_images.back),
+    ) else {
+        return;
+    };
+    let params = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("game_of_life_params"),
+        contents: &request.params,
+        usage: BufferUsages::UNIFORM,
+    });
+    let bind_group = |input: &TextureView, output: &TextureView| {
+        render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("game_of_life_bind_group"),
+            layout: &pipeline.layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(input),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(output),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        })
+    };
+    commands.insert_resource(LifeBindGroups {
+        groups: [
+            bind_group(&front.texture_view, &back.texture_view),
+            bind_group(&back.texture_view, &front.texture_view),
+        ],
+    });
+
+    if request.readback {
+        let padded_row_bytes =
+            (request.size[0] * 4).div_ceil(COPY_ROW_ALIGNMENT) * COPY_ROW_ALIGNMENT;
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("game_of_life_readback"),
+            size: (padded_row_bytes * request.size[1]) as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        commands.insert_resource(ReadbackBuffer {
+            buffer,
+            padded_row_bytes,
+        });
+    }
+}
+
+/// Maps the buffer filled by [`LifeNode`] and sends the unpadded pixels back
+/// to the main world.
+fn map_readback(
+    mut commands: Commands,
+    readback: Option<Res<ReadbackBuffer>>,
+    request: Option<Res<GpuStepRequest>>,
+    sender: Res<ReadbackSender>,
+    render_device: Res<RenderDevice>,
+) {
+    let (Some(readback), Some(request)) = (readback, request) else {
+        return;
+    };
+    let slice = readback.buffer.slice(..);
+    render_device.map_buffer(&slice, MapMode::Read, |_| {});
+    render_device.poll(wgpu::Maintain::Wait);
+
+    let row_bytes = (request.size[0] * 4) as usize;
+    let pixels = slice
+        .get_mapped_range()
+        .chunks(readback.padded_row_bytes as usize)
+        .flat_map(|row| row[..row_bytes].to_vec())
+        .collect();
+    readback.buffer.unmap();
+    let _ = sender.0.lock().unwrap().send(pixels);
+    commands.remove_resource::<ReadbackBuffer>();
+}
+
+struct LifeNode;
+
+impl render_graph::Node for LifeNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let (Some(bind_groups), Some(request), Some(board_images)) = (
+            world.get_resource::<LifeBindGroups>(),
+            world.get_resource::<GpuStepRequest>(),
+            world.get_resource::<GpuBoardImages>(),
+        ) else {
+            return Ok(());
+        };
+        let gpu_images = world.resource::<RenderAssets<Image>>();
+        let (Some(front), Some(back)) = (
+            gpu_images.get(&board_images.front),
+            gpu_images.get(&board_images.back),
+        ) else {
+            return Ok(());
+        };
+        let pipeline = world.resource::<LifePipeline>();
+        let Some(compute_pipeline) = world
+            .resource::<PipelineCache>()
+            .get_compute_pipeline(pipeline.pipeline)
+        else {
+            return Ok(());
+        };
+        let size = Extent3d {
+            width: request.size[0],
+            height: request.size[1],
+            depth_or_array_layers: 1,
+        };
+        let encoder = render_context.command_encoder();
+
+        for step in 0..request.steps {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+            pass.set_pipeline(compute_pipeline);
+            pass.set_bind_group(0, &bind_groups.groups[step as usize % 2], &[]);
+            pass.dispatch_workgroups(
+                size.width.div_ceil(WORKGROUP_SIZE),
+                size.height.div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+        if request.steps % 2 == 1 {
+            encoder.copy_texture_to_texture(
+                back.texture.as_image_copy(),
+                front.texture.as_image_copy(),
+                size,
+            );
+        }
+        if let Some(readback) = world.get_resource::<ReadbackBuffer>() {
+            encoder.copy_texture_to_buffer(
+                front.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &readback.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: NonZeroU32::new(readback.padded_row_bytes),
+                        rows_per_image: None,
+                    },
+                },
+                size,
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+// One Life generation: reads the current board from `input` and writes the
+// next one to `output`. A cell is alive when its alpha is above one half.
+
+struct Params {
+    color: vec4<f32>,
+    // Bit n is set when n neighbors cause a birth / let a cell survive.
+    birth: u32,
+    survival: u32,
+    // 0 = torus, 1 = dead border, 2 = mirror
+    boundary: u32,
+    _padding: u32,
+}
+
+@group(0) @binding(0)
+var input: texture_2d<f32>;
+@group(0) @binding(1)
+var output: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2)
+var<uniform> params: Params;
+
+fn resolve(coord: i32, len: i32) -> i32 {
+    if coord >= 0 && coord < len {
+        return coord;
+    }
+    if params.boundary == 0u {
+        return (coord + len) % len;
+    }
+    if params.boundary == 1u {
+        return -1;
+    }
+    if coord < 0 {
+        return -coord - 1;
+    }
+    return 2 * len - coord - 1;
+}
+
+fn is_alive(location: vec2<i32>, size: vec2<i32>) -> i32 {
+    let x = resolve(location.x, size.x);
+    let y = resolve(location.y, size.y);
+    if x < 0 || y < 0 {
+        return 0;
+    }
+    return i32(textureLoad(input, vec2<i32>(x, y), 0).a > 0.5);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(input));
+    let location = vec2<i32>(invocation_id.xy);
+    if location.x >= size.x || location.y >= size.y {
+        return;
+    }
+
+    var neighbors = 0;
+    for (var dy = -1; dy <= 1; dy = dy + 1) {
+        for (var dx = -1; dx <= 1; dx = dx + 1) {
+            if dx != 0 || dy != 0 {
+                neighbors = neighbors + is_alive(location + vec2<i32>(dx, dy), size);
+            }
+        }
+    }
+
+    let alive = is_alive(location, size) == 1;
+    let mask = select(params.birth, params.survival, alive);
+    let next = ((mask >> u32(neighbors)) & 1u) == 1u;
+    textureStore(output, location, select(vec4<f32>(0.0), params.color, next));
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::config::Settings;
+use crate::{BoardEntity, GameData};
+
+const LINE_COLOR: Color = Color::rgba(0.5, 0.5, 0.5, 0.35);
+const LINE_WIDTH: f32 = 1.0;
+
+/// Parent of the grid line sprites, so they can be hidden all at once.
+#[derive(Component)]
+pub struct GridLines;
+
+pub fn spawn_grid_lines(mut commands: Commands, game_data: Res<GameData>, settings: Res<Settings>) {
+    let cell_size = settings.cell_size;
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let board_size = Vec2::new(width as f32, height as f32) * cell_size;
+    let line = |size: Vec2, position: Vec2| SpriteBundle {
+        sprite: Sprite {
+            color: LINE_COLOR,
+            custom_size: Some(size),
+            ..Default::default()
+        },
+        transform: Transform::from_translation(position.extend(2.0)),
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            SpatialBundle {
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            GridLines,
+            BoardEntity,
+        ))
+        .with_children(|parent| {
+            for x in 0..=width {
+                parent.spawn(line(
+                    Vec2::new(LINE_WIDTH, board_size.y),
+                    Vec2::new(x as f32 * cell_size, board_size.y / 2.0),
+                ));
+            }
+            for y in 0..=height {
+                parent.spawn(line(
+                    Vec2::new(board_size.x, LINE_WIDTH),
+                    Vec2::new(board_size.x / 2.0, y as f32 * cell_size),
+                ));
+            }
+        });
+}
+
+/// `G` shows or hides the grid lines.
+pub fn toggle_grid_lines(actions: Actions, mut grids: Query<&mut Visibility, With<GridLines>>) {
+    if actions.just_pressed(Action::GridLines) {
+        for mut visibility in grids.iter_mut() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Visible,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
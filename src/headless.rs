@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use crate::board::{Board, BoundaryMode};
+use crate::config::Settings;
+use crate::stats::StatsLog;
+use crate::topology::GridTopology;
+use crate::{save, GameData, SimulationTick};
+
+/// Runs `generations` steps without a window, one per app update, then
+/// prints how long it took and the final population.
+pub fn run(settings: Settings, generations: u64) {
+    // Every update steps, including the first.
+    if generations == 0 {
+        return;
+    }
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(GameData::new(&settings))
+        .insert_resource(settings.boundary)
+        .insert_resource(settings.topology)
+        .insert_resource(StatsLog::new(&settings))
+        .insert_resource(SimulationTick {
+            timer: Timer::new(Duration::from_millis(settings.tick), TimerMode::Repeating),
+        })
+        .insert_resource(settings)
+        .add_startup_system(save::load_from_args)
+        .add_system(headless_step);
+
+    // The first update only runs startup systems and a first step; time
+    // everything after loading.
+    app.update();
+    let start = Instant::now();
+    for _ in 1..generations {
+        app.update();
+    }
+    let elapsed = start.elapsed();
+
+    let game_data = app.world.resource::<GameData>();
+    let per_second = generations.saturating_sub(1) as f64 / elapsed.as_secs_f64();
+    println!("generations: {}", game_data.generation);
+    println!(
+        "elapsed:     {:.3}s ({per_second:.1} generations/s)",
+        elapsed.as_secs_f64()
+    );
+    println!("population:  {}", game_data.board.population());
+}
+
+fn headless_step(
+    mut game_data: ResMut<GameData>,
+    mut next_board: Local<Board>,
+    boundary: Res<BoundaryMode>,
+    topology: Res<GridTopology>,
+    mut stats: ResMut<StatsLog>,
+) {
+    stats.step(&mut game_data, &mut next_board, *boundary, *topology);
+}
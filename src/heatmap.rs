@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::bindings::{Action, Actions};
+use crate::config::Settings;
+use crate::{BoardEntity, GameData};
+
+/// Fraction of a cell's heat left after each generation it spends dead.
+const DECAY: f32 = 0.9;
+const HEAT_COLOR: [u8; 3] = [255, 96, 0];
+
+/// A decaying trace of where cells have recently been alive.
+#[derive(Resource, Default)]
+pub struct Heatmap {
+    pub enabled: bool,
+    intensity: Vec<f32>,
+    image: Handle<Image>,
+    last_generation: u64,
+}
+
+#[derive(Component)]
+pub struct HeatmapLayer;
+
+pub fn setup_heatmap(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut heatmap: ResMut<Heatmap>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    heatmap.image = images.add(image);
+    heatmap.intensity = vec![0.0; width * height];
+
+    let board_size = Vec2::new(width as f32, height as f32) * settings.cell_size;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(board_size),
+                ..Default::default()
+            },
+            texture: heatmap.image.clone(),
+            // Behind the live cells.
+            transform: Transform::from_translation((board_size / 2.0).extend(-1.0)),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        HeatmapLayer,
+        BoardEntity,
+    ));
+}
+
+impl Heatmap {
+    /// Heats up live cells and cools everything else, once per generation.
+    pub fn record(&mut self, game_data: &GameData) {
+        if !self.enabled || self.last_generation == game_data.generation {
+            return;
+        }
+        self.last_generation = game_data.generation;
+        let board = &game_data.board;
+        self.intensity.resize(board.width() * board.height(), 0.0);
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                let heat = &mut self.intensity[y * board.width() + x];
+                *heat = if board.get(x, y) { 1.0 } else { *heat * DECAY };
+            }
+        }
+    }
+}
+
+/// Records a generation stepped outside the simulation tick, which records
+/// its own, and redraws the layer.
+pub fn update_heatmap(
+    mut heatmap: ResMut<Heatmap>,
+    mut images: ResMut<Assets<Image>>,
+    game_data: Res<GameData>,
+) {
+    if !heatmap.enabled || !game_data.is_changed() {
+        return;
+    }
+    heatmap.record(&game_data);
+
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let image = heatmap.image.clone();
+    let Some(image) = images.get_mut(&image) else {
+        return;
+    };
+    for y in 0..height {
+        let row = height - 1 - y;
+        for x in 0..width {
+            let heat = heatmap.intensity[y * width + x];
+            let offset = (row * width + x) * 4;
+            image.data[offset..offset + 3].copy_from_slice(&HEAT_COLOR);
+            image.data[offset + 3] = (heat * 160.0) as u8;
+        }
+    }
+}
+
+/// `H` shows or hides the heatmap layer.
+pub fn toggle_heatmap(
+    actions: Actions,
+    mut heatmap: ResMut<Heatmap>,
+    mut layers: Query<&mut Visibility, With<HeatmapLayer>>,
+) {
+    if actions.just_pressed(Action::Heatmap) {
+        heatmap.enabled = !heatmap.enabled;
+        for mut visibility in layers.iter_mut() {
+            *visibility = if heatmap.enabled {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
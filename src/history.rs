@@ -0,0 +1,119 @@
+//! A rolling record of recent generations that can be scrubbed through.
+//!
+//! Left arrow pauses and steps back one generation, Right arrow forward
+//! again, with Shift moving ten at a time. Stepping on from a rewound
+//! generation throws away the ones that followed it. Only Life keeps a
+//! history, since the other automata hold extra state off the board.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::config::{Automaton, Settings};
+use crate::{GameData, SimulationTick};
+
+struct Frame {
+    generation: u64,
+    board: Board,
+    decay: Vec<u8>,
+}
+
+#[derive(Resource, Default)]
+pub struct History {
+    frames: VecDeque<Frame>,
+    /// Index of the frame on the board while rewound.
+    cursor: Option<usize>,
+}
+
+impl History {
+    /// Adds the board if it is at a new generation, dropping the oldest
+    /// past [`Settings::history_length`].
+    pub fn record(&mut self, game_data: &GameData, settings: &Settings) {
+        if game_data.automaton != Automaton::Life || game_data.replay.is_some() {
+            return;
+        }
+        let generation = game_data.generation;
+        let viewing = match self.cursor {
+            Some(cursor) => self.frames[cursor].generation,
+            None => self
+                .frames
+                .back()
+                .map_or(u64::MAX, |frame| frame.generation),
+        };
+        if viewing == generation {
+            return;
+        }
+        if let Some(cursor) = self.cursor.take() {
+            self.frames.truncate(cursor + 1);
+        }
+        // The board was restarted or loaded at an earlier generation.
+        while self
+            .frames
+            .back()
+            .is_some_and(|frame| frame.generation >= generation)
+        {
+            self.frames.pop_back();
+        }
+        self.frames.push_back(Frame {
+            generation,
+            board: game_data.board.clone(),
+            decay: game_data.decay.clone(),
+        });
+        while self.frames.len() > settings.history_length.max(1) {
+            self.frames.pop_front();
+        }
+    }
+}
+
+/// Records generations stepped outside the simulation tick, such as by a
+/// [`crate::StepEvent`], along with boards that were restarted or loaded.
+pub fn record_history(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    mut history: ResMut<History>,
+) {
+    history.record(&game_data, &settings);
+}
+
+pub fn scrub_history(
+    actions: Actions,
+    keyboard: Res<Input<KeyCode>>,
+    mut game_data: ResMut<GameData>,
+    mut history: ResMut<History>,
+    mut sim_tick: ResMut<SimulationTick>,
+) {
+    let back = actions.just_pressed(Action::HistoryBack);
+    if !back && !actions.just_pressed(Action::HistoryForward) {
+        return;
+    }
+    let Some(last) = history.frames.len().checked_sub(1) else {
+        return;
+    };
+    let shift = keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let distance = if shift { 10 } else { 1 };
+    let current = history.cursor.unwrap_or(last);
+    let target = if back {
+        current.saturating_sub(distance)
+    } else {
+        (current + distance).min(last)
+    };
+    if !sim_tick.timer.paused() {
+        sim_tick.toggle_pause();
+    }
+
+    let frame = &history.frames[target];
+    game_data.board = frame.board.clone();
+    game_data.decay = frame.decay.clone();
+    game_data.generation = frame.generation;
+    // Ages restart from here, as they do for a loaded board.
+    game_data.ages.clear();
+    info!(
+        "generation {} ({} of {} kept)",
+        frame.generation,
+        target + 1,
+        history.frames.len()
+    );
+    history.cursor = (target != last).then_some(target);
+}
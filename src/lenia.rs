@@ -0,0 +1,186 @@
+//! Lenia, a continuous automaton: every cell holds a value between 0 and 1
+//! and grows or shrinks according to a smooth ring-shaped weighted sum of
+//! its surroundings.
+//!
+//! The values live in [`GameData::lenia`]. The board marks cells above
+//! [`VISIBLE`] so drawing, saving and the editing tools keep working; cells
+//! set on the board by other tools become fully alive and cells cleared
+//! there become empty.
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::board::BoundaryMode;
+use crate::config::Settings;
+use crate::GameData;
+
+/// Values below this count as empty on the board.
+const VISIBLE: f32 = 0.05;
+
+/// Settings for the Lenia automaton, read from the `[lenia]` table of the
+/// config file. The defaults give the classic Orbium gliders.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeniaSettings {
+    /// Kernel radius in cells
+    pub radius: usize,
+    /// Neighborhood density that growth is centered on
+    pub mu: f32,
+    /// Width of the growth curve
+    pub sigma: f32,
+    /// Fraction of the growth applied each generation
+    pub dt: f32,
+}
+
+impl Default for LeniaSettings {
+    fn default() -> Self {
+        LeniaSettings {
+            radius: 13,
+            mu: 0.15,
+            sigma: 0.015,
+            dt: 0.1,
+        }
+    }
+}
+
+pub struct Lenia {
+    settings: LeniaSettings,
+    /// Cell values, row by row like the board.
+    field: Vec<f32>,
+    /// Offsets and weights of the kernel, weights summing to one.
+    kernel: Vec<([isize; 2], f32)>,
+}
+
+impl Lenia {
+    pub fn new(settings: LeniaSettings) -> Self {
+        let r = settings.radius.max(1) as isize;
+        let mut kernel: Vec<([isize; 2], f32)> = (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| [dx, dy]))
+            .filter_map(|[dx, dy]| {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt() / r as f32;
+                (distance > 0.0 && distance < 1.0).then(|| {
+                    let bump = (4.0 - 1.0 / (distance * (1.0 - distance))).exp();
+                    ([dx, dy], bump)
+                })
+            })
+            .collect();
+        let total: f32 = kernel.iter().map(|(_, weight)| weight).sum();
+        for (_, weight) in kernel.iter_mut() {
+            *weight /= total;
+        }
+        Lenia {
+            settings,
+            field: Vec::new(),
+            kernel,
+        }
+    }
+
+    fn growth(&self, density: f32) -> f32 {
+        let LeniaSettings { mu, sigma, .. } = self.settings;
+        2.0 * (-(density - mu).powi(2) / (2.0 * sigma * sigma)).exp() - 1.0
+    }
+}
+
+/// The value of the cell at `[x, y]`, taking board edits that have not
+/// reached the field yet into account.
+pub fn value(game_data: &GameData, x: usize, y: usize) -> f32 {
+    let value = game_data
+        .lenia
+        .field
+        .get(y * game_data.board.width() + x)
+        .copied()
+        .unwrap_or(0.0);
+    match (game_data.board.get(x, y), value >= VISIBLE) {
+        (false, _) => 0.0,
+        (true, false) => 1.0,
+        (true, true) => value,
+    }
+}
+
+fn sync_field(game_data: &mut GameData) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let field: Vec<f32> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| [x, y]))
+        .map(|[x, y]| value(game_data, x, y))
+        .collect();
+    game_data.lenia.field = field;
+}
+
+fn store_field(game_data: &mut GameData, field: Vec<f32>) {
+    let width = game_data.board.width();
+    for (i, &value) in field.iter().enumerate() {
+        game_data.board.set(i % width, i / width, value >= VISIBLE);
+    }
+    game_data.lenia.field = field;
+}
+
+/// Fills the field with random values, each cell being non-empty with
+/// probability `density`. With a density of zero a random patch is placed in
+/// the middle instead, since an empty field stays empty.
+pub fn reseed(game_data: &mut GameData, density: f64) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let mut rng = ChaCha8Rng::seed_from_u64(game_data.seed);
+    let patch = game_data.lenia.settings.radius * 2;
+    let in_patch =
+        |x: usize, y: usize| x.abs_diff(width / 2) < patch && y.abs_diff(height / 2) < patch;
+    let field = (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width, i / width);
+            let seeded = if density > 0.0 {
+                rng.gen_bool(density.min(1.0))
+            } else {
+                in_patch(x, y)
+            };
+            if seeded {
+                rng.gen()
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    store_field(game_data, field);
+}
+
+/// Applies one growth step to every cell.
+pub fn step(game_data: &mut GameData, boundary: BoundaryMode) {
+    sync_field(game_data);
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    if width == 0 {
+        return;
+    }
+    let lenia = &game_data.lenia;
+    let field = &lenia.field;
+    let mut next = vec![0.0; field.len()];
+    next.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let density: f32 = lenia
+                .kernel
+                .iter()
+                .filter_map(|&([dx, dy], weight)| {
+                    let x = boundary.resolve(x as isize + dx, width)?;
+                    let y = boundary.resolve(y as isize + dy, height)?;
+                    Some(field[y * width + x] * weight)
+                })
+                .sum();
+            let current = field[y * width + x];
+            *cell = (current + lenia.settings.dt * lenia.growth(density)).clamp(0.0, 1.0);
+        }
+    });
+    store_field(game_data, next);
+}
+
+/// Maps a cell value onto a dark-to-bright colormap, or `None` if the cell
+/// is empty.
+pub fn color(game_data: &GameData, settings: &Settings, x: usize, y: usize) -> Option<Color> {
+    let value = value(game_data, x, y);
+    if value < VISIBLE {
+        return None;
+    }
+    let low = Vec4::from(Color::rgb(0.1, 0.0, 0.4).as_rgba_f32());
+    let high = Vec4::from(settings.cell_color().as_rgba_f32());
+    let rgba = low.lerp(high, value);
+    Some(Color::rgba(rgba.x, rgba.y, rgba.z, rgba.w))
+}
@@ -0,0 +1,701 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+mod age;
+mod bindings;
+mod board;
+#[cfg(feature = "pattern-browser")]
+mod browser;
+mod brush;
+mod camera;
+mod cell_style;
+mod census;
+mod colors;
+mod compare;
+mod config;
+mod display;
+mod edit;
+mod elementary;
+mod export;
+mod gamepad;
+mod generations;
+mod gpu;
+mod grid_lines;
+mod headless;
+mod heatmap;
+mod history;
+mod lenia;
+mod life3d;
+mod macrocell;
+mod minimap;
+mod neighbor_counts;
+mod neighborhood;
+#[cfg(feature = "multiplayer")]
+mod net;
+mod panel;
+mod pattern;
+mod periodicity;
+mod population;
+mod replay;
+mod rule;
+mod save;
+mod script;
+mod selection;
+mod sonify;
+mod state;
+mod stats;
+mod stream;
+mod texture;
+mod theme;
+mod topology;
+mod touch;
+mod turmite;
+mod wireworld;
+
+pub use bindings::{Action, Binding, Keybindings};
+pub use board::{Board, BoundaryMode};
+pub use census::run as run_census;
+pub use compare::run as run_compare;
+pub use config::{Automaton, Backend, CellShape, Cli, PresentationMode, RenderMode, Settings};
+pub use export::AnimationFormat;
+pub use headless::run as run_headless;
+pub use lenia::LeniaSettings;
+pub use life3d::{run as run_3d, Life3dSettings, Rule3d};
+pub use neighborhood::Neighborhood;
+pub use pattern::Pattern;
+pub use rule::Rule;
+pub use sonify::MusicalScale;
+pub use state::AppState;
+pub use stream::StreamFormat;
+pub use theme::Theme;
+pub use topology::GridTopology;
+pub use turmite::TurnRule;
+
+use bindings::Actions;
+use brush::{Brush, Symmetry};
+use camera::CursorCell;
+use selection::{ClipboardPattern, PendingPaste, Selection};
+use state::InGame;
+
+/// Most generations the CPU backend runs in a single frame.
+const MAX_STEPS_PER_FRAME: u32 = 100;
+
+/// Runs the simulation, its rendering and its controls inside an app.
+/// Add it after `DefaultPlugins`; the window itself is left to the app.
+pub struct GameOfLifePlugin {
+    pub settings: Settings,
+}
+
+impl GameOfLifePlugin {
+    pub fn new(settings: Settings) -> Self {
+        GameOfLifePlugin { settings }
+    }
+}
+
+impl Default for GameOfLifePlugin {
+    fn default() -> Self {
+        GameOfLifePlugin::new(Settings::default())
+    }
+}
+
+impl Plugin for GameOfLifePlugin {
+    fn build(&self, app: &mut App) {
+        let settings = self.settings.clone();
+        app.insert_resource(ClearColor(settings.background_color()))
+            .insert_resource(GameData::new(&settings))
+            .insert_resource(settings.keybindings.clone())
+            .insert_resource(stats::StatsLog::new(&settings))
+            .insert_resource(settings.boundary)
+            .insert_resource(settings.topology)
+            .init_resource::<Selection>()
+            .init_resource::<ClipboardPattern>()
+            .init_resource::<PendingPaste>()
+            .init_resource::<history::History>()
+            .init_resource::<camera::CameraGlide>()
+            .init_resource::<heatmap::Heatmap>()
+            .init_resource::<neighbor_counts::NeighborCounts>()
+            .init_resource::<brush::Brush>()
+            .init_resource::<brush::Symmetry>()
+            .init_resource::<gamepad::GamepadCursor>()
+            .init_resource::<wireworld::WirePalette>()
+            .init_resource::<colors::ColorPalette>()
+            .init_resource::<export::AnimationCapture>()
+            .init_resource::<population::PopulationHistory>()
+            .init_resource::<periodicity::PeriodDetector>()
+            .insert_resource(SimulationTick {
+                timer: Timer::new(Duration::from_millis(settings.tick), TimerMode::Repeating),
+            })
+            .add_event::<StepEvent>()
+            .add_event::<SetCellEvent>()
+            .add_plugin(gpu::GpuLifePlugin)
+            .add_plugin(panel::SettingsPanelPlugin)
+            .add_plugin(sonify::SonifyPlugin)
+            .add_plugin(state::AppStatePlugin)
+            .insert_resource(config::LaunchSettings(settings.clone()))
+            .insert_resource(settings)
+            .add_startup_system(camera::setup_camera)
+            .add_startup_system(spawn_cells.run_if(sprite_mode))
+            .add_startup_system(texture::spawn_board_texture.run_if(texture::texture_mode))
+            .add_startup_system(topology::spawn_hex_cells.run_if(topology::hex_mode))
+            .add_systems(
+                (
+                    spawn_cells.run_if(sprite_mode),
+                    texture::spawn_board_texture.run_if(texture::texture_mode),
+                    topology::spawn_hex_cells.run_if(topology::hex_mode),
+                    heatmap::setup_heatmap,
+                    neighbor_counts::setup_neighbor_counts,
+                    grid_lines::spawn_grid_lines,
+                )
+                    .distributive_run_if(on_event::<panel::BoardResized>()),
+            )
+            .add_startup_system(log_seed)
+            .add_startup_system(save::load_from_args.in_base_set(StartupSet::PreStartup))
+            .add_startup_system(selection::setup_selection_outline)
+            .add_startup_system(selection::setup_paste_preview)
+            .add_startup_system(heatmap::setup_heatmap)
+            .add_startup_system(neighbor_counts::setup_neighbor_counts)
+            .add_startup_system(grid_lines::spawn_grid_lines)
+            .add_startup_system(brush::setup_brush_preview)
+            .add_startup_system(gamepad::setup_gamepad_cursor)
+            .add_startup_system(population::spawn_population_graph)
+            .add_startup_system(minimap::spawn_minimap)
+            .add_system(render_board.run_if(sprite_mode))
+            .add_system(texture::update_board_texture.run_if(texture::texture_mode))
+            .add_system(topology::render_hex_cells.run_if(topology::hex_mode))
+            .add_system(tick_simulation.after(pause_sim))
+            .add_system(
+                execute_step
+                    .after(tick_simulation)
+                    .in_set(OnUpdate(AppState::Run))
+                    .run_if(not(gpu::gpu_backend))
+                    .run_if(step_due),
+            )
+            .add_system(handle_events.run_if(not(gpu::gpu_backend)))
+            .add_system(pause_sim.in_set(InGame))
+            .add_system(camera::fit_camera_to_window)
+            .add_system(camera::pan_and_zoom.in_set(InGame))
+            .add_system(camera::fit_and_follow_hotkeys.in_set(InGame))
+            .add_system(display::toggle_fullscreen)
+            .add_system(display::apply_present_mode)
+            .add_system(
+                camera::glide_camera
+                    .after(camera::fit_and_follow_hotkeys)
+                    .after(camera::pan_and_zoom),
+            )
+            .add_system(cycle_boundary.in_set(InGame))
+            .add_system(
+                add_cells
+                    .run_if(not(wireworld::wireworld_mode))
+                    .run_if(not(colors::colored_mode))
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(
+                wireworld::paint_wires
+                    .run_if(wireworld::wireworld_mode)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(
+                wireworld::select_wire_palette
+                    .run_if(wireworld::wireworld_mode)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(
+                colors::paint_colors
+                    .run_if(colors::colored_mode)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(
+                colors::select_color_palette
+                    .run_if(colors::colored_mode)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(turmite::render_ants.run_if(turmite::turmite_mode))
+            .add_system(
+                elementary::change_elementary_rule
+                    .run_if(elementary::elementary_mode)
+                    .in_set(InGame),
+            )
+            .add_system(script::reload_script.run_if(script::script_mode))
+            .add_system(gamepad::gamepad_input.in_set(InGame))
+            .add_system(
+                gamepad::gamepad_paint
+                    .after(gamepad::gamepad_input)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(touch::touch_paint.in_set(OnUpdate(AppState::Edit)))
+            .add_system(touch::touch_pinch_zoom.in_set(InGame))
+            .add_system(brush::cycle_brush.in_set(OnUpdate(AppState::Edit)))
+            .add_system(brush::cycle_symmetry.in_set(OnUpdate(AppState::Edit)))
+            .add_system(brush::update_brush_preview.in_set(OnUpdate(AppState::Edit)))
+            .add_system(selection::select_region.in_set(OnUpdate(AppState::Edit)))
+            .add_system(selection::edit_selection.in_set(OnUpdate(AppState::Edit)))
+            .add_system(
+                selection::transform_selection
+                    .before(history::scrub_history)
+                    .before(elementary::change_elementary_rule)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(selection::render_selection)
+            .add_system(selection::begin_paste.in_set(OnUpdate(AppState::Edit)))
+            .add_system(
+                selection::place_paste
+                    .before(add_cells)
+                    .before(wireworld::paint_wires)
+                    .before(colors::paint_colors)
+                    .in_set(OnUpdate(AppState::Edit)),
+            )
+            .add_system(selection::update_paste_preview.in_set(OnUpdate(AppState::Edit)))
+            .add_system(save::save_load_hotkeys.in_set(InGame))
+            .add_system(macrocell::export_macrocell.in_set(InGame))
+            .add_system(replay::toggle_recording.in_set(InGame))
+            .add_system(
+                export::start_animation_capture
+                    .run_if(not(gpu::gpu_backend))
+                    .in_set(InGame),
+            )
+            .add_system(export::capture_animation_frames.after(execute_step))
+            .add_system(export::save_screenshot.in_set(InGame))
+            .add_system(population::draw_population_graph.after(execute_step))
+            .add_system(population::toggle_population_graph.in_set(InGame))
+            .add_system(minimap::update_minimap.after(camera::pan_and_zoom))
+            .add_system(
+                minimap::jump_to_minimap_click
+                    .before(add_cells)
+                    .before(wireworld::paint_wires)
+                    .before(colors::paint_colors)
+                    .before(selection::select_region)
+                    .in_set(InGame),
+            )
+            .add_system(
+                history::record_history
+                    .after(execute_step)
+                    .after(handle_events)
+                    .run_if(not(gpu::gpu_backend)),
+            )
+            .add_system(
+                history::scrub_history
+                    .after(history::record_history)
+                    .run_if(not(gpu::gpu_backend))
+                    .in_set(InGame),
+            )
+            .add_system(
+                periodicity::detect_periodicity
+                    .after(execute_step)
+                    .run_if(not(gpu::gpu_backend)),
+            )
+            .add_system(replay::capture_frame_edits.in_base_set(CoreSet::Last))
+            .add_startup_system(replay::follow_playback.in_base_set(StartupSet::PreStartup))
+            .add_system(replay::follow_playback.in_base_set(CoreSet::First))
+            .add_system(edit::adjust_density.in_set(InGame))
+            .add_system(edit::reseed_board.in_set(InGame))
+            .add_system(edit::board_hotkeys.in_set(OnUpdate(AppState::Edit)))
+            .add_system(age::toggle_age_coloring.in_set(InGame))
+            .add_system(theme::cycle_theme.in_set(InGame))
+            .add_system(heatmap::update_heatmap.after(execute_step))
+            .add_system(
+                heatmap::toggle_heatmap
+                    .run_if(topology::square_topology)
+                    .in_set(InGame),
+            )
+            .add_system(
+                neighbor_counts::toggle_neighbor_counts
+                    .run_if(topology::square_topology)
+                    .in_set(InGame),
+            )
+            .add_system(
+                neighbor_counts::update_neighbor_counts
+                    .after(execute_step)
+                    .after(neighbor_counts::toggle_neighbor_counts),
+            )
+            .add_system(
+                grid_lines::toggle_grid_lines
+                    .run_if(topology::square_topology)
+                    .in_set(InGame),
+            )
+            .add_startup_system(stream::start_server)
+            .add_system(
+                stream::serve_board
+                    .in_base_set(CoreSet::Last)
+                    .run_if(resource_exists::<stream::BoardServer>()),
+            )
+            .add_system(config::save_config_on_exit.in_base_set(CoreSet::Last));
+
+        #[cfg(feature = "pattern-browser")]
+        app.init_resource::<browser::PatternBrowser>()
+            .add_system(
+                browser::search_patterns
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(bevy::input::InputSystem),
+            )
+            .add_system(browser::receive_pattern);
+
+        #[cfg(feature = "multiplayer")]
+        app.add_startup_system(net::start_session).add_system(
+            net::sync_board
+                .in_base_set(CoreSet::Last)
+                .run_if(resource_exists::<net::Session>()),
+        );
+    }
+}
+
+/// Send to advance the simulation by one generation, even while paused.
+pub struct StepEvent;
+
+/// Send to make the cell at `[x, y]` alive or dead.
+pub struct SetCellEvent {
+    pub x: usize,
+    pub y: usize,
+    pub alive: bool,
+}
+
+/// The board and everything the simulation tracks about it.
+#[derive(Resource)]
+pub struct GameData {
+    pub board: Board,
+    pub generation: u64,
+    pub automaton: Automaton,
+    pub rule: Rule,
+    /// Seed the initial soup was generated from, so it can be reproduced.
+    seed: u64,
+    /// Generations each cell has been alive, row by row like the board.
+    ages: Vec<u16>,
+    /// Dying state of each cell under a Generations rule, row by row.
+    decay: Vec<u8>,
+    /// Cell states while running Wireworld, row by row.
+    wires: Vec<wireworld::WireState>,
+    /// Color of each live cell under Immigration or QuadLife, row by row.
+    colors: Vec<u8>,
+    /// What the last step changed, so quiet parts of the board are skipped.
+    activity: board::Activity,
+    turmites: turmite::Turmites,
+    elementary: elementary::Elementary,
+    lenia: lenia::Lenia,
+    /// The compiled rule script, when running one.
+    script: Option<script::RuleScript>,
+    /// The replay being recorded or played back, if any.
+    replay: Option<replay::Replay>,
+}
+
+impl GameData {
+    /// A new board as described by `settings`, including the random soup
+    /// and the `pattern` file.
+    pub fn new(settings: &Settings) -> Self {
+        let mut game_data = GameData {
+            board: Board::new(settings.width, settings.height),
+            generation: 0,
+            automaton: settings.automaton,
+            rule: settings.rule,
+            seed: settings.seed().unwrap_or_else(|| rand::thread_rng().gen()),
+            ages: Vec::new(),
+            decay: Vec::new(),
+            wires: Vec::new(),
+            colors: Vec::new(),
+            activity: board::Activity::default(),
+            turmites: turmite::Turmites::new(
+                settings.ant_rule.clone(),
+                settings.ants,
+                settings.width,
+                settings.height,
+            ),
+            elementary: elementary::Elementary::new(settings.elementary_rule),
+            lenia: lenia::Lenia::new(settings.lenia.clone()),
+            script: None,
+            replay: None,
+        };
+        if let (Automaton::Script, Some(path)) = (settings.automaton, &settings.script) {
+            match script::RuleScript::load(path) {
+                Ok(script) => game_data.script = Some(script),
+                Err(err) => eprintln!("failed to load {}: {err}", path.display()),
+            }
+        }
+        // These start from a seed row or patch even without a density.
+        let seeded = matches!(settings.automaton, Automaton::Elementary | Automaton::Lenia);
+        if seeded || settings.density > 0.0 {
+            game_data.reseed(game_data.seed, settings.density);
+        }
+        if let Some(path) = &settings.pattern {
+            match Pattern::load(path) {
+                Ok(pattern) => pattern.stamp_centered(&mut game_data.board),
+                Err(err) => eprintln!("failed to load {}: {err}", path.display()),
+            }
+        }
+        if let Some(path) = &settings.replay {
+            if let Err(err) = replay::start_playback(&mut game_data, path) {
+                eprintln!("failed to load {}: {err}", path.display());
+            }
+        }
+        game_data
+    }
+
+    /// Advances one generation, using `next_board` as the back buffer.
+    pub fn step(&mut self, next_board: &mut Board, boundary: BoundaryMode, topology: GridTopology) {
+        let (boundary, topology) = replay::before_step(self, boundary, topology);
+        match self.automaton {
+            Automaton::Life | Automaton::Immigration | Automaton::QuadLife => {
+                self.step_life(next_board, boundary, topology)
+            }
+            Automaton::Wireworld => wireworld::step(self, boundary, topology),
+            Automaton::Turmite => turmite::step(self, boundary),
+            Automaton::Elementary => elementary::step(self, boundary),
+            Automaton::Lenia => lenia::step(self, boundary),
+            Automaton::Script => script::step(self, boundary),
+        }
+        self.generation += 1;
+        replay::after_step(self);
+    }
+
+    fn step_life(
+        &mut self,
+        next_board: &mut Board,
+        boundary: BoundaryMode,
+        topology: GridTopology,
+    ) {
+        if next_board.width() != self.board.width() || next_board.height() != self.board.height() {
+            *next_board = self.board.clone();
+        }
+        if topology.is_hex() {
+            self.board
+                .step_with_offsets(next_board, self.rule, boundary, &topology::HEX_NEIGHBORS);
+        } else if self.rule.is_classic() {
+            self.board
+                .step_active(next_board, self.rule, boundary, &mut self.activity);
+        } else {
+            self.board.step_range(next_board, self.rule, boundary);
+        }
+        std::mem::swap(&mut self.board, next_board);
+        generations::update_decay(self, next_board);
+        colors::update_colors(self, next_board, boundary, topology);
+        age::update_ages(self);
+    }
+
+    /// Replaces the board with a fresh random soup and restarts the count.
+    fn reseed(&mut self, seed: u64, density: f64) {
+        self.seed = seed;
+        self.generation = 0;
+        match self.automaton {
+            Automaton::Elementary => elementary::restart(self, density),
+            Automaton::Lenia => lenia::reseed(self, density),
+            _ => {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                self.board.randomize(&mut rng, density);
+                colors::randomize(self, &mut rng);
+            }
+        }
+    }
+}
+
+/// Marks entities drawn at the size of the board, which are despawned and
+/// spawned again when it is resized.
+#[derive(Component)]
+pub struct BoardEntity;
+
+/// The sprite for the board cell at `[x, y]`. One is spawned per cell up
+/// front and only its visibility changes afterwards.
+#[derive(Component)]
+struct CellComponent {
+    x: usize,
+    y: usize,
+}
+
+#[derive(Resource)]
+struct SimulationTick {
+    timer: Timer,
+}
+
+impl SimulationTick {
+    fn toggle_pause(&mut self) {
+        if self.timer.paused() {
+            self.timer.unpause();
+        } else {
+            self.timer.pause();
+        }
+    }
+
+    /// Multiplies the time between generations by `factor`, keeping it
+    /// between 1ms and 2s.
+    fn scale_interval(&mut self, factor: f32) {
+        let interval = self.timer.duration().mul_f32(factor);
+        self.timer
+            .set_duration(interval.clamp(Duration::from_millis(1), Duration::from_secs(2)));
+    }
+}
+
+fn log_seed(game_data: Res<GameData>, settings: Res<Settings>) {
+    if settings.density > 0.0 {
+        info!("random soup seed: {}", game_data.seed);
+    }
+}
+
+fn pause_sim(actions: Actions, mut sim_tick: ResMut<SimulationTick>) {
+    if actions.just_pressed(Action::Pause) {
+        sim_tick.toggle_pause();
+    }
+}
+
+fn sprite_mode(settings: Res<Settings>, topology: Res<GridTopology>) -> bool {
+    settings.backend == Backend::Cpu
+        && settings.render_mode == RenderMode::Sprites
+        && !topology.is_hex()
+}
+
+fn spawn_cells(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+) {
+    let cell_size = settings.cell_size;
+    let cell_color = settings.cell_color();
+    let texture = cell_style::cell_texture(&settings, &mut images, &asset_server);
+    for x in 0..game_data.board.width() {
+        for y in 0..game_data.board.height() {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(cell_style::sprite_size(&settings)),
+                        color: cell_color,
+                        ..Default::default()
+                    },
+                    texture: texture.clone(),
+                    transform: Transform {
+                        translation: Vec3::from([
+                            (x as f32 * cell_size) + cell_size / 2.0,
+                            (y as f32 * cell_size) + cell_size / 2.0,
+                            0.0,
+                        ]),
+                        ..Default::default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                CellComponent { x, y },
+                BoardEntity,
+            ));
+        }
+    }
+}
+
+fn render_board(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    mut cells: Query<(&CellComponent, &mut Visibility, &mut Sprite)>,
+) {
+    if !game_data.is_changed() && !settings.is_changed() {
+        return;
+    }
+    for (cell, mut visibility, mut sprite) in cells.iter_mut() {
+        let color = age::cell_color(&game_data, &settings, cell.x, cell.y);
+        let target = if color.is_some() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target {
+            *visibility = target;
+        }
+        if let Some(color) = color {
+            if sprite.color != color {
+                sprite.color = color;
+            }
+        }
+    }
+}
+
+/// Advances the tick timer by the frame time. Both backends then run one
+/// generation for every interval that elapsed, so the simulation keeps the
+/// same pace whatever the frame rate.
+///
+/// It ticks in every state: outside [`AppState::Run`] the timer is paused,
+/// and ticking a paused timer clears its count of finished intervals.
+fn tick_simulation(time: Res<Time>, mut sim_tick: ResMut<SimulationTick>) {
+    sim_tick.timer.tick(time.delta());
+}
+
+fn step_due(sim_tick: Res<SimulationTick>) -> bool {
+    sim_tick.timer.times_finished_this_tick() > 0
+}
+
+/// Runs the generations that came due this frame. After a long stall only
+/// [`MAX_STEPS_PER_FRAME`] are caught up, rather than freezing to catch up
+/// on all of them.
+#[allow(clippy::too_many_arguments)]
+fn execute_step(
+    mut game_data: ResMut<GameData>,
+    mut next_board: Local<Board>,
+    boundary: Res<BoundaryMode>,
+    topology: Res<GridTopology>,
+    sim_tick: Res<SimulationTick>,
+    settings: Res<Settings>,
+    mut population: ResMut<population::PopulationHistory>,
+    mut history: ResMut<history::History>,
+    mut capture: ResMut<export::AnimationCapture>,
+    mut heatmap: ResMut<heatmap::Heatmap>,
+    mut periods: ResMut<periodicity::PeriodDetector>,
+    mut stats: ResMut<stats::StatsLog>,
+) {
+    let steps = sim_tick
+        .timer
+        .times_finished_this_tick()
+        .min(MAX_STEPS_PER_FRAME);
+    for _ in 0..steps {
+        stats.step(&mut game_data, &mut next_board, *boundary, *topology);
+        population.record(game_data.board.population());
+        history.record(&game_data, &settings);
+        capture.record(&game_data, &settings);
+        heatmap.record(&game_data);
+        periods.record(&game_data);
+    }
+    if steps > 0 {
+        stats.flush();
+    }
+}
+
+/// Applies [`StepEvent`]s and [`SetCellEvent`]s sent by the app.
+fn handle_events(
+    mut steps: EventReader<StepEvent>,
+    mut set_cells: EventReader<SetCellEvent>,
+    mut game_data: ResMut<GameData>,
+    mut next_board: Local<Board>,
+    boundary: Res<BoundaryMode>,
+    topology: Res<GridTopology>,
+) {
+    for event in set_cells.iter() {
+        if event.x < game_data.board.width() && event.y < game_data.board.height() {
+            game_data.board.set(event.x, event.y, event.alive);
+        }
+    }
+    for _ in steps.iter() {
+        game_data.step(&mut next_board, *boundary, *topology);
+    }
+}
+
+fn cycle_boundary(actions: Actions, mut boundary: ResMut<BoundaryMode>) {
+    if actions.just_pressed(Action::CycleBoundary) {
+        *boundary = boundary.next();
+        info!("boundary mode: {:?}", *boundary);
+    }
+}
+
+fn add_cells(
+    mut game_data: ResMut<GameData>,
+    brush: Res<Brush>,
+    symmetry: Res<Symmetry>,
+    cursor: CursorCell,
+    actions: Actions,
+) {
+    if actions.pressed(Action::Draw) {
+        let Some([x, y]) = cursor.cell(&game_data.board) else {
+            return;
+        };
+        let (width, height) = (game_data.board.width(), game_data.board.height());
+        for [dx, dy] in brush.offsets() {
+            for [x, y] in symmetry.images([x as isize + dx, y as isize + dy], width, height) {
+                game_data.board.set(x as usize, y as usize, true);
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! An experimental three-dimensional Life, started with `--3d`.
+//!
+//! It runs as its own app with its own board and camera, so nothing in the
+//! 2D simulation changes. Rules use Bays's notation: `5766` means a live
+//! cell survives with 5 to 7 of its 26 neighbors and a dead one is born with
+//! exactly 6.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Settings;
+use crate::{pause_sim, SimulationTick};
+
+/// Settings for `--3d`, read from the `[life3d]` table of the config file.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Life3dSettings {
+    /// Board size in cells along x, y and z
+    pub size: [usize; 3],
+    #[serde(with = "crate::config::rule_string")]
+    pub rule: Rule3d,
+    /// Probability that each cell starts alive
+    pub density: f64,
+}
+
+impl Default for Life3dSettings {
+    fn default() -> Self {
+        Life3dSettings {
+            size: [32, 32, 32],
+            rule: Rule3d::default(),
+            density: 0.15,
+        }
+    }
+}
+
+/// Survival and birth neighbor counts out of the 26 surrounding cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule3d {
+    pub survival: [usize; 2],
+    pub birth: [usize; 2],
+}
+
+impl Rule3d {
+    fn next_state(self, alive: bool, neighbors: usize) -> bool {
+        let [lo, hi] = if alive { self.survival } else { self.birth };
+        (lo..=hi).contains(&neighbors)
+    }
+}
+
+impl Default for Rule3d {
+    fn default() -> Self {
+        "5766".parse().unwrap()
+    }
+}
+
+/// Four single digits (`4555`) or, for counts above nine, four
+/// comma-separated numbers (`10,13,9,9`).
+impl FromStr for Rule3d {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let numbers: Vec<String> = if s.contains(',') {
+            s.split(',').map(|n| n.trim().to_string()).collect()
+        } else {
+            s.chars().map(String::from).collect()
+        };
+        let counts = numbers
+            .iter()
+            .map(|n| match n.parse::<usize>() {
+                Ok(n) if n <= 26 => Ok(n),
+                _ => Err(format!("invalid neighbor count `{n}`")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let &[s_lo, s_hi, b_lo, b_hi] = counts.as_slice() else {
+            return Err(format!("3D rule `{s}` needs four counts"));
+        };
+        Ok(Rule3d {
+            survival: [s_lo, s_hi],
+            birth: [b_lo, b_hi],
+        })
+    }
+}
+
+impl fmt::Display for Rule3d {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = [self.survival, self.birth].concat();
+        if counts.iter().all(|&n| n < 10) {
+            counts.iter().try_for_each(|n| write!(f, "{n}"))
+        } else {
+            let counts: Vec<String> = counts.iter().map(|n| n.to_string()).collect();
+            write!(f, "{}", counts.join(","))
+        }
+    }
+}
+
+/// The voxel board. It always wraps around on every axis.
+#[derive(Resource)]
+pub struct Board3d {
+    size: [usize; 3],
+    cells: Vec<bool>,
+    rule: Rule3d,
+    seed: u64,
+}
+
+impl Board3d {
+    fn new(settings: &Life3dSettings, seed: u64) -> Self {
+        let [w, h, d] = settings.size;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Board3d {
+            size: settings.size,
+            cells: (0..w * h * d)
+                .map(|_| rng.gen_bool(settings.density.clamp(0.0, 1.0)))
+                .collect(),
+            rule: settings.rule,
+            seed,
+        }
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        let [w, h, _] = self.size;
+        (z * h + y) * w + x
+    }
+
+    fn step(&mut self) {
+        let [w, h, d] = self.size;
+        if w * h * d == 0 {
+            return;
+        }
+        let wrap = |c: usize, delta: isize, len: usize| {
+            (c as isize + delta).rem_euclid(len as isize) as usize
+        };
+        let cells = &self.cells;
+        let rule = self.rule;
+        let mut next = vec![false; cells.len()];
+        next.par_chunks_mut(w * h)
+            .enumerate()
+            .for_each(|(z, slice)| {
+                for y in 0..h {
+                    for x in 0..w {
+                        let mut neighbors = 0;
+                        for dz in -1..=1 {
+                            for dy in -1..=1 {
+                                for dx in -1..=1 {
+                                    if (dx, dy, dz) == (0, 0, 0) {
+                                        continue;
+                                    }
+                                    let (nx, ny, nz) =
+                                        (wrap(x, dx, w), wrap(y, dy, h), wrap(z, dz, d));
+                                    neighbors += cells[(nz * h + ny) * w + nx] as usize;
+                                }
+                            }
+                        }
+                        let alive = cells[(z * h + y) * w + x];
+                        slice[y * w + x] = rule.next_state(alive, neighbors);
+                    }
+                }
+            });
+        self.cells = next;
+    }
+}
+
+/// One cube per cell. They share a single mesh and material and only their
+/// visibility changes.
+#[derive(Component)]
+struct Voxel([usize; 3]);
+
+/// Spherical coordinates of the camera around the board's center.
+#[derive(Resource)]
+struct OrbitCamera {
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+/// Opens the 3D app and runs it until the window is closed.
+pub fn run(settings: Settings) {
+    let seed = settings.seed().unwrap_or_else(|| rand::thread_rng().gen());
+    let board = Board3d::new(&settings.life3d, seed);
+    let distance = board.size.iter().copied().max().unwrap_or(1) as f32 * 2.0;
+
+    App::new()
+        .insert_resource(ClearColor(settings.background_color()))
+        .insert_resource(board)
+        .insert_resource(OrbitCamera {
+            yaw: 0.6,
+            pitch: 0.5,
+            distance,
+        })
+        .insert_resource(SimulationTick {
+            timer: Timer::new(Duration::from_millis(settings.tick), TimerMode::Repeating),
+        })
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: String::from("Cellular Automata 3D"),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .insert_resource(settings)
+        .add_startup_system(setup_scene)
+        .add_system(step_board)
+        .add_system(render_voxels)
+        .add_system(orbit_camera)
+        .add_system(pause_sim)
+        .run();
+}
+
+fn setup_scene(
+    mut commands: Commands,
+    board: Res<Board3d>,
+    settings: Res<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    info!("random soup seed: {}", board.seed);
+    let mesh = meshes.add(Mesh::from(shape::Cube { size: 0.9 }));
+    let material = materials.add(StandardMaterial::from(settings.cell_color()));
+    let [w, h, d] = board.size;
+    let center = Vec3::new(w as f32, h as f32, d as f32) / 2.0;
+    for z in 0..d {
+        for y in 0..h {
+            for x in 0..w {
+                commands.spawn((
+                    PbrBundle {
+                        mesh: mesh.clone(),
+                        material: material.clone(),
+                        transform: Transform::from_translation(
+                            Vec3::new(x as f32, y as f32, z as f32) + 0.5 - center,
+                        ),
+                        visibility: Visibility::Hidden,
+                        ..Default::default()
+                    },
+                    Voxel([x, y, z]),
+                ));
+            }
+        }
+    }
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(1.0, 2.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..Default::default()
+    });
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 0.3,
+    });
+    commands.spawn(Camera3dBundle::default());
+}
+
+fn step_board(mut board: ResMut<Board3d>, time: Res<Time>, mut sim_tick: ResMut<SimulationTick>) {
+    sim_tick.timer.tick(time.delta());
+    if sim_tick.timer.just_finished() {
+        board.step();
+    }
+}
+
+fn render_voxels(board: Res<Board3d>, mut voxels: Query<(&Voxel, &mut Visibility)>) {
+    if !board.is_changed() {
+        return;
+    }
+    for (voxel, mut visibility) in voxels.iter_mut() {
+        let target = if board.cells[board.index(voxel.0)] {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target {
+            *visibility = target;
+        }
+    }
+}
+
+/// Left-drag orbits around the board, the wheel moves closer or further.
+fn orbit_camera(
+    mouse: Res<Input<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut orbit: ResMut<OrbitCamera>,
+    mut cameras: Query<&mut Transform, With<Camera3d>>,
+) {
+    let drag: Vec2 = motion.iter().map(|event| event.delta).sum();
+    if mouse.pressed(MouseButton::Left) {
+        orbit.yaw -= drag.x * 0.01;
+        orbit.pitch = (orbit.pitch + drag.y * 0.01).clamp(-1.5, 1.5);
+    }
+    for event in wheel.iter() {
+        orbit.distance = (orbit.distance * 0.9f32.powf(event.y)).max(1.0);
+    }
+    let rotation = Quat::from_euler(EulerRot::YXZ, orbit.yaw, -orbit.pitch, 0.0);
+    for mut transform in cameras.iter_mut() {
+        transform.translation = rotation * Vec3::new(0.0, 0.0, orbit.distance);
+        transform.look_at(Vec3::ZERO, Vec3::Y);
+    }
+}
@@ -0,0 +1,304 @@
+//! Golly's macrocell (`.mc`) format, which stores a pattern as a quadtree
+//! with identical subtrees written once. Huge, sparse or repetitive
+//! patterns that would be impractical as RLE stay small.
+//!
+//! Each line after the header is a node, numbered from 1 in order. Lines
+//! starting with `.`, `*` or `$` are 8×8 leaves drawn row by row; the rest
+//! read `level nw ne sw se`, naming a `2^level` square by its quadrants,
+//! with 0 standing for an empty one. The last node is the whole pattern.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::pattern::Pattern;
+use crate::GameData;
+
+const LEAF_LEVEL: u32 = 3;
+const LEAF_SIZE: usize = 1 << LEAF_LEVEL;
+const EXPORT_PATH: &str = "game_of_life.mc";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    /// Bit `x` of `rows[y]` is the cell `x` from the left in row `y` from
+    /// the top.
+    Leaf([u8; LEAF_SIZE]),
+    Branch {
+        level: u32,
+        children: [usize; 4],
+    },
+}
+
+impl Node {
+    fn level(&self) -> u32 {
+        match *self {
+            Node::Leaf(_) => LEAF_LEVEL,
+            Node::Branch { level, .. } => level,
+        }
+    }
+}
+
+/// Reads a macrocell file into a pattern just large enough for its live
+/// cells.
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut nodes: Vec<Option<Node>> = vec![None];
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('[') || line.starts_with('#') {
+            continue;
+        }
+        let node = if line.starts_with(['.', '*', '$']) {
+            let mut rows = [0u8; LEAF_SIZE];
+            let (mut x, mut y) = (0, 0);
+            for c in line.chars() {
+                match c {
+                    '$' => (x, y) = (0, y + 1),
+                    '.' | '*' if x < LEAF_SIZE && y < LEAF_SIZE => {
+                        rows[y] |= u8::from(c == '*') << x;
+                        x += 1;
+                    }
+                    _ => return Err(format!("bad leaf `{line}`")),
+                }
+            }
+            Node::Leaf(rows)
+        } else {
+            let numbers: Vec<usize> = line
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| format!("bad node `{line}`")))
+                .collect::<Result<_, _>>()?;
+            let &[level, nw, ne, sw, se] = numbers.as_slice() else {
+                return Err(format!("bad node `{line}`"));
+            };
+            let children = [nw, ne, sw, se];
+            let valid = |&child: &usize| {
+                child == 0
+                    || matches!(nodes.get(child), Some(Some(node)) if node.level() + 1 == level as u32)
+            };
+            // Cell coordinates are `usize`, so a wider square can't be placed.
+            if level > usize::BITS as usize {
+                return Err(format!("node `{line}` is too large"));
+            }
+            if level as u32 <= LEAF_LEVEL || !children.iter().all(valid) {
+                return Err(format!("bad node `{line}`"));
+            }
+            Node::Branch {
+                level: level as u32,
+                children,
+            }
+        };
+        nodes.push(Some(node));
+    }
+    let nodes: Vec<Node> = nodes.into_iter().flatten().collect();
+    let Some(root) = nodes.last() else {
+        return Ok(Pattern::default());
+    };
+
+    let mut live = Vec::new();
+    collect_cells(&nodes, root, 0, 0, &mut live);
+    let (Some(min_x), Some(min_y)) = (
+        live.iter().map(|&(x, _)| x).min(),
+        live.iter().map(|&(_, y)| y).min(),
+    ) else {
+        return Ok(Pattern::default());
+    };
+    let width = live.iter().map(|&(x, _)| x - min_x + 1).max().unwrap_or(0);
+    let height = live.iter().map(|&(_, y)| y - min_y + 1).max().unwrap_or(0);
+    let mut cells = vec![false; width * height];
+    for (x, y) in live {
+        cells[(y - min_y) * width + (x - min_x)] = true;
+    }
+    Ok(Pattern {
+        width,
+        height,
+        cells,
+    })
+}
+
+/// Pushes the `[x, y]` (top down) of every live cell under `node`, whose
+/// top-left corner is at `[left, top]`. `nodes` are numbered from 1.
+fn collect_cells(
+    nodes: &[Node],
+    node: &Node,
+    left: usize,
+    top: usize,
+    live: &mut Vec<(usize, usize)>,
+) {
+    match *node {
+        Node::Leaf(rows) => {
+            for (y, row) in rows.iter().enumerate() {
+                for x in (0..LEAF_SIZE).filter(|x| row & (1 << x) != 0) {
+                    live.push((left + x, top + y));
+                }
+            }
+        }
+        Node::Branch { level, children } => {
+            let half = 1 << (level - 1);
+            let corners = [(0, 0), (half, 0), (0, half), (half, half)];
+            for (child, (dx, dy)) in children.into_iter().zip(corners) {
+                if child != 0 {
+                    collect_cells(nodes, &nodes[child - 1], left + dx, top + dy, live);
+                }
+            }
+        }
+    }
+}
+
+/// Builds the quadtree for the board, writing each distinct node once.
+struct Writer<'a> {
+    board: &'a Board,
+    ids: HashMap<Node, usize>,
+    lines: Vec<String>,
+}
+
+impl Writer<'_> {
+    /// Whether the cell `[x, y]` counted from the top left is alive.
+    fn get(&self, x: usize, y: usize) -> bool {
+        x < self.board.width()
+            && y < self.board.height()
+            && self.board.get(x, self.board.height() - 1 - y)
+    }
+
+    /// The id of the `2^level` square at `[left, top]`, or 0 if it is empty.
+    fn node(&mut self, level: u32, left: usize, top: usize) -> usize {
+        let node = if level == LEAF_LEVEL {
+            let mut rows = [0u8; LEAF_SIZE];
+            for (y, row) in rows.iter_mut().enumerate() {
+                for x in 0..LEAF_SIZE {
+                    *row |= u8::from(self.get(left + x, top + y)) << x;
+                }
+            }
+            if rows == [0; LEAF_SIZE] {
+                return 0;
+            }
+            Node::Leaf(rows)
+        } else {
+            let half = 1 << (level - 1);
+            let children = [
+                self.node(level - 1, left, top),
+                self.node(level - 1, left + half, top),
+                self.node(level - 1, left, top + half),
+                self.node(level - 1, left + half, top + half),
+            ];
+            if children == [0; 4] {
+                return 0;
+            }
+            Node::Branch { level, children }
+        };
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        self.lines.push(match node {
+            Node::Leaf(rows) => {
+                let used = rows.iter().rposition(|&row| row != 0).map_or(0, |y| y + 1);
+                rows[..used]
+                    .iter()
+                    .map(|&row| {
+                        let width = (u8::BITS - row.leading_zeros()) as usize;
+                        let mut line: String = (0..width)
+                            .map(|x| if row & (1 << x) != 0 { '*' } else { '.' })
+                            .collect();
+                        line.push('$');
+                        line
+                    })
+                    .collect()
+            }
+            Node::Branch { level, children } => {
+                let [nw, ne, sw, se] = children;
+                format!("{level} {nw} {ne} {sw} {se}")
+            }
+        });
+        self.ids.insert(node, self.lines.len());
+        self.lines.len()
+    }
+}
+
+/// Renders the board as a macrocell file.
+pub fn write(game_data: &GameData) -> String {
+    let board = &game_data.board;
+    let size = board.width().max(board.height()).max(LEAF_SIZE);
+    let level = size
+        .next_power_of_two()
+        .trailing_zeros()
+        .max(LEAF_LEVEL + 1);
+    let mut writer = Writer {
+        board,
+        ids: HashMap::new(),
+        lines: Vec::new(),
+    };
+    if writer.node(level, 0, 0) == 0 {
+        // An empty pattern still needs a root.
+        writer.lines.push(format!("{level} 0 0 0 0"));
+    }
+    let mut text = format!(
+        "[M2] (game_of_life)\n#R {}\n#G {}\n",
+        game_data.rule, game_data.generation
+    );
+    for line in writer.lines {
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+/// Ctrl+E exports the board as a macrocell file.
+pub fn export_macrocell(actions: Actions, game_data: Res<GameData>) {
+    if !actions.just_pressed(Action::ExportMacrocell) {
+        return;
+    }
+    match save(Path::new(EXPORT_PATH), &game_data) {
+        Ok(()) => info!("saved {EXPORT_PATH}"),
+        Err(err) => error!("failed to save {EXPORT_PATH}: {err}"),
+    }
+}
+
+fn save(path: &Path, game_data: &GameData) -> io::Result<()> {
+    fs::write(path, write(game_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+
+    #[test]
+    fn round_trips_a_random_board() {
+        let settings = Settings {
+            width: 40,
+            height: 30,
+            density: 0.3,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let game_data = GameData::new(&settings);
+        let board = &game_data.board;
+        let live: Vec<(usize, usize)> = (0..board.height())
+            .flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| board.get(x, y))
+            .collect();
+        let min = [
+            live.iter().map(|&(x, _)| x).min().unwrap(),
+            live.iter().map(|&(_, y)| y).min().unwrap(),
+        ];
+        let max = [
+            live.iter().map(|&(x, _)| x).max().unwrap(),
+            live.iter().map(|&(_, y)| y).max().unwrap(),
+        ];
+        let expected = Pattern::from_region(board, min, max);
+
+        let pattern = parse(&write(&game_data)).unwrap();
+        assert_eq!(
+            (pattern.width, pattern.height),
+            (expected.width, expected.height)
+        );
+        assert!(pattern.cells == expected.cells);
+    }
+
+    #[test]
+    fn rejects_levels_wider_than_usize() {
+        assert!(parse("[M2]\n65 0 0 0 0\n").is_err());
+    }
+}
@@ -1,202 +1,46 @@
-use std::time::Duration;
-
 use bevy::prelude::*;
-use bevy::window::{PresentMode, WindowResolution};
+use bevy::window::WindowResolution;
 
-const GRID_WIDTH: usize = 128;
-const GRID_HEIGHT: usize = 96;
-const CELL_SIZE: f32 = 5.0;
+use game_of_life::{GameOfLifePlugin, Settings};
 
 fn main() {
+    let settings = Settings::load();
+    if settings.run_3d {
+        game_of_life::run_3d(settings);
+        return;
+    }
+    if let Some(soups) = settings.census {
+        game_of_life::run_census(settings, soups);
+        return;
+    }
+    if !settings.compare.is_empty() {
+        let rules = std::iter::once(settings.rule)
+            .chain(settings.compare.iter().copied())
+            .collect();
+        game_of_life::run_compare(settings, rules);
+        return;
+    }
+    if let Some(generations) = settings.headless {
+        game_of_life::run_headless(settings, generations);
+        return;
+    }
+
     App::new()
-        .insert_resource(ClearColor(Color::BLACK))
-        .init_resource::<GameData>()
-        .insert_resource(SimulationTick {
-            timer: Timer::new(Duration::from_millis(50), TimerMode::Repeating),
-        })
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: String::from("Cellular Automata"),
                 resolution: WindowResolution::new(
-                    (GRID_WIDTH as f32) * CELL_SIZE,
-                    (GRID_HEIGHT as f32) * CELL_SIZE,
+                    (settings.width as f32) * settings.cell_size,
+                    (settings.height as f32) * settings.cell_size,
                 ),
-                present_mode: PresentMode::AutoVsync,
-                resizable: false,
+                present_mode: settings.present_mode.into(),
+                resizable: true,
+                canvas: Some(String::from("#game-of-life")),
+                fit_canvas_to_parent: true,
                 ..Default::default()
             }),
             ..Default::default()
         }))
-        .add_startup_system(setup_camera)
-        .add_system(render_board)
-        .add_system(execute_step)
-        .add_system(pause_sim)
-        .add_system(add_cells)
+        .add_plugin(GameOfLifePlugin::new(settings))
         .run();
 }
-
-#[derive(Clone, Copy)]
-struct Cell {
-    alive: bool,
-}
-
-#[derive(Resource)]
-struct GameData {
-    board: [[Cell; GRID_WIDTH]; GRID_HEIGHT],
-}
-
-impl Default for GameData {
-    fn default() -> Self {
-        GameData {
-            board: [[Cell { alive: false }; GRID_WIDTH]; GRID_HEIGHT],
-        }
-    }
-}
-
-#[derive(Component)]
-struct CellComponent;
-
-#[derive(Resource)]
-struct SimulationTick {
-    timer: Timer,
-}
-
-fn setup_camera(mut commands: Commands) {
-    let board_width = GRID_WIDTH as f32 * CELL_SIZE;
-    let board_height = GRID_HEIGHT as f32 * CELL_SIZE;
-
-    commands.spawn(Camera2dBundle {
-        transform: Transform {
-            translation: Vec3::from([board_width / 2.0, board_height / 2.0, 500.0]),
-            ..Default::default()
-        },
-        ..Default::default()
-    });
-}
-
-fn pause_sim(keyboard: Res<Input<KeyCode>>, mut sim_tick: ResMut<SimulationTick>) {
-    if keyboard.any_just_pressed([KeyCode::Space]) {
-        if sim_tick.timer.paused() {
-            sim_tick.timer.unpause();
-        } else {
-            sim_tick.timer.pause();
-        }
-    }
-}
-
-fn render_board(
-    mut commands: Commands,
-    game_data: Res<GameData>,
-    cells: Query<Entity, With<CellComponent>>,
-) {
-    // if !sim_tick.timer.just_finished() {
-    //     return;
-    // }
-    for cell in cells.iter() {
-        commands.entity(cell).despawn();
-    }
-    for x in 0..GRID_WIDTH {
-        for y in 0..GRID_HEIGHT {
-            if game_data.board[y][x].alive {
-                commands.spawn((
-                    SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::from([CELL_SIZE, CELL_SIZE])),
-                            color: { Color::WHITE },
-                            ..Default::default()
-                        },
-                        transform: Transform {
-                            translation: Vec3::from([
-                                (x as f32 * CELL_SIZE) + CELL_SIZE / 2.0,
-                                (y as f32 * CELL_SIZE) + CELL_SIZE / 2.0,
-                                0.0,
-                            ]),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    },
-                    CellComponent,
-                ));
-            }
-        }
-    }
-}
-
-fn execute_step(
-    mut game_data: ResMut<GameData>,
-    time: Res<Time>,
-    mut sim_tick: ResMut<SimulationTick>,
-) {
-    sim_tick.timer.tick(time.delta());
-    let old_board = game_data.board.clone();
-    if sim_tick.timer.just_finished() {
-        for x in 0..GRID_WIDTH {
-            for y in 0..GRID_HEIGHT {
-                let surrounding = surrounding_count(&old_board, [x, y]);
-                if !old_board[y][x].alive && surrounding == 3 {
-                    game_data.board[y][x].alive = true;
-                } else if old_board[y][x].alive && surrounding == 2 || surrounding == 3 {
-                } else {
-                    game_data.board[y][x].alive = false;
-                }
-            }
-        }
-    }
-}
-
-fn surrounding_count(board: &[[Cell; GRID_WIDTH]; GRID_HEIGHT], current: [usize; 2]) -> usize {
-    let directions: Vec<[isize; 2]> = vec![
-        [-1, -1],
-        [0, -1],
-        [1, -1],
-        [-1, 0],
-        [1, 0],
-        [-1, 1],
-        [0, 1],
-        [1, 1],
-    ];
-    let mut count: usize = 0;
-    for direction in directions.iter() {
-        let new_x = if current[0] == 0 {
-            GRID_WIDTH - 1
-        } else if current[0] == GRID_WIDTH - 1 {
-            0
-        } else {
-            (current[0] as isize + direction[0]) as usize
-        };
-        let new_y = if current[1] == 0 {
-            GRID_HEIGHT - 1
-        } else if current[1] == GRID_HEIGHT - 1 {
-            0
-        } else {
-            (current[1] as isize + direction[1]) as usize
-        };
-        if board[new_y][new_x].alive {
-            count += 1;
-        }
-    }
-    count
-}
-
-fn add_cells(
-    mut game_data: ResMut<GameData>,
-    windows: Query<&Window>,
-    mouse: Res<Input<MouseButton>>,
-) {
-    if mouse.pressed(MouseButton::Left) {
-        let main_window = windows.get_single().unwrap();
-        if let Some(position) = main_window.cursor_position() {
-            let x = if position.x >= (GRID_WIDTH as f32 * CELL_SIZE) {
-                GRID_WIDTH - 1
-            } else {
-                (position.x / CELL_SIZE) as usize
-            };
-            let y = if position.y > (GRID_HEIGHT as f32 * CELL_SIZE) {
-                GRID_HEIGHT
-            } else {
-                (position.y / CELL_SIZE) as usize
-            };
-            game_data.board[y][x].alive = true;
-        }
-    }
-}
@@ -0,0 +1,161 @@
+//! An overview of the whole board in the bottom-right corner, one pixel per
+//! cell, with the part the camera shows outlined. It only appears while some
+//! of the board is out of view, and clicking it moves the camera there.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::window::PrimaryWindow;
+
+use crate::camera::{self, CursorCell, MainCamera};
+use crate::config::Settings;
+use crate::texture;
+use crate::topology::GridTopology;
+use crate::{age, GameData};
+
+/// Longest side of the minimap on screen, in logical pixels.
+const MAX_SIDE: f32 = 200.0;
+const VIEW_COLOR: [u8; 4] = [255, 200, 0, 255];
+
+#[derive(Component)]
+pub struct MinimapNode;
+
+pub fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(8.0),
+                    right: Val::Px(8.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            image: UiImage {
+                texture: images.add(Image::default()),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        Interaction::default(),
+        MinimapNode,
+    ));
+}
+
+/// Redraws the minimap, resizing it to the board when needed.
+pub fn update_minimap(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+    cursor: CursorCell,
+    cameras: Query<(Ref<Transform>, Ref<OrthographicProjection>), With<MainCamera>>,
+    mut images: ResMut<Assets<Image>>,
+    mut nodes: Query<(&UiImage, &mut Style, &mut Visibility), With<MinimapNode>>,
+) {
+    let (Ok((transform, projection)), Ok((ui_image, mut style, mut visibility))) =
+        (cameras.get_single(), nodes.get_single_mut())
+    else {
+        return;
+    };
+    let Some(view) = cursor.visible_rect() else {
+        return;
+    };
+    let board_size = camera::board_size(&game_data, &settings, *topology);
+    let board_rect = Rect::from_corners(Vec2::ZERO, board_size);
+    if view.contains(board_rect.min) && view.contains(board_rect.max) {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    if !game_data.is_changed() && !transform.is_changed() && !projection.is_changed() {
+        return;
+    }
+
+    let board = &game_data.board;
+    let (width, height) = (board.width(), board.height());
+    let Some(image) = images.get_mut(&ui_image.texture) else {
+        return;
+    };
+    if image.size() != Vec2::new(width as f32, height as f32) {
+        *image = Image::new_fill(
+            Extent3d {
+                width: width as u32,
+                height: height as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        image.sampler_descriptor = ImageSampler::nearest();
+        let scale = MAX_SIDE / width.max(height) as f32;
+        style.size = Size::new(
+            Val::Px(width as f32 * scale),
+            Val::Px(height as f32 * scale),
+        );
+    }
+
+    let mut background = settings.background_color();
+    background.set_a(0.8);
+    let background = texture::rgba_u8(background);
+    // The outline of the view, in pixels with rows running top down.
+    let to_pixel = |point: Vec2| {
+        let cell = point / board_size * Vec2::new(width as f32, height as f32);
+        (
+            cell.x.floor() as isize,
+            height as isize - 1 - cell.y.floor() as isize,
+        )
+    };
+    let (left, bottom) = to_pixel(view.min);
+    let (right, top) = to_pixel(view.max);
+    for y in 0..height {
+        let row = height - 1 - y;
+        for x in 0..width {
+            let (px, py) = (x as isize, row as isize);
+            let inside = (left..=right).contains(&px) && (top..=bottom).contains(&py);
+            let on_edge = px == left || px == right || py == top || py == bottom;
+            let pixel = if inside && on_edge {
+                VIEW_COLOR
+            } else {
+                age::cell_color(&game_data, &settings, x, y).map_or(background, texture::rgba_u8)
+            };
+            let offset = (row * width + x) * 4;
+            image.data[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+}
+
+/// Clicking the minimap centers the camera on that spot. The click is used
+/// up so it doesn't also paint the board underneath.
+pub fn jump_to_minimap_click(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    nodes: Query<(&Interaction, &Node, &GlobalTransform, &Visibility), With<MinimapNode>>,
+    mut cameras: Query<&mut Transform, With<MainCamera>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+) {
+    let Ok((interaction, node, node_transform, visibility)) = nodes.get_single() else {
+        return;
+    };
+    if *interaction != Interaction::Clicked || *visibility == Visibility::Hidden {
+        return;
+    }
+    let (Ok(window), Ok(mut camera)) = (windows.get_single(), cameras.get_single_mut()) else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    // UI positions run from the top left, the cursor from the bottom left.
+    let cursor = Vec2::new(cursor.x, window.height() - cursor.y);
+    let top_left = node_transform.translation().truncate() - node.size() / 2.0;
+    let fraction = ((cursor - top_left) / node.size()).clamp(Vec2::ZERO, Vec2::ONE);
+    let board_size = camera::board_size(&game_data, &settings, *topology);
+    let target = Vec2::new(fraction.x, 1.0 - fraction.y) * board_size;
+    camera.translation = target.extend(camera.translation.z);
+    mouse.reset(MouseButton::Left);
+}
@@ -0,0 +1,132 @@
+//! A debug layer coloring every cell by how many live neighbors it has,
+//! from blue for one up to red for a full neighborhood. `D` toggles it; it
+//! only shows while the simulation is paused, so the counts always match
+//! the board on screen.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::bindings::{Action, Actions};
+use crate::board::BoundaryMode;
+use crate::config::Settings;
+use crate::texture;
+use crate::{BoardEntity, GameData, SimulationTick};
+
+const ALPHA: f32 = 0.6;
+
+#[derive(Resource, Default)]
+pub struct NeighborCounts {
+    enabled: bool,
+    /// Whether the layer was on screen last frame.
+    shown: bool,
+    image: Handle<Image>,
+}
+
+#[derive(Component)]
+pub struct NeighborCountLayer;
+
+pub fn setup_neighbor_counts(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut counts: ResMut<NeighborCounts>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    counts.image = images.add(image);
+    counts.shown = false;
+
+    let board_size = Vec2::new(width as f32, height as f32) * settings.cell_size;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(board_size),
+                ..Default::default()
+            },
+            texture: counts.image.clone(),
+            // Over the live cells, under the selection outline.
+            transform: Transform::from_translation((board_size / 2.0).extend(0.5)),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        NeighborCountLayer,
+        BoardEntity,
+    ));
+}
+
+/// `D` turns the layer on or off.
+pub fn toggle_neighbor_counts(actions: Actions, mut counts: ResMut<NeighborCounts>) {
+    if actions.just_pressed(Action::NeighborCounts) {
+        counts.enabled = !counts.enabled;
+        info!(
+            "neighbor counts: {}",
+            if counts.enabled { "on" } else { "off" }
+        );
+    }
+}
+
+/// Blue through green and yellow to red as `count` goes from one to `max`.
+fn ramp(count: usize, max: usize) -> [u8; 4] {
+    if count == 0 {
+        return [0; 4];
+    }
+    let t = (count - 1) as f32 / max.saturating_sub(1).max(1) as f32;
+    texture::rgba_u8(Color::hsla(240.0 * (1.0 - t), 1.0, 0.5, ALPHA))
+}
+
+/// Shows the layer while it is on and the simulation is paused, redrawing
+/// it whenever the board changes.
+pub fn update_neighbor_counts(
+    mut counts: ResMut<NeighborCounts>,
+    mut images: ResMut<Assets<Image>>,
+    game_data: Res<GameData>,
+    boundary: Res<BoundaryMode>,
+    sim_tick: Res<SimulationTick>,
+    mut layers: Query<&mut Visibility, With<NeighborCountLayer>>,
+) {
+    let visible = counts.enabled && sim_tick.timer.paused();
+    let redraw = visible && (!counts.shown || game_data.is_changed() || boundary.is_changed());
+    if visible != counts.shown {
+        counts.shown = visible;
+        for mut visibility in &mut layers {
+            *visibility = if visible {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+    if !redraw {
+        return;
+    }
+
+    let Some(image) = images.get_mut(&counts.image) else {
+        return;
+    };
+    let board = &game_data.board;
+    let rule = game_data.rule;
+    let max = (-(rule.range as isize)..=rule.range as isize)
+        .map(|dy| 2 * rule.neighborhood.reach(rule.range, dy) + 1)
+        .sum::<usize>()
+        - 1;
+    for y in 0..board.height() {
+        let row = board.height() - 1 - y;
+        for x in 0..board.width() {
+            let count = board.neighbor_count(x, y, rule, *boundary);
+            let offset = (row * board.width() + x) * 4;
+            image.data[offset..offset + 4].copy_from_slice(&ramp(count, max));
+        }
+    }
+}
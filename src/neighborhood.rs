@@ -0,0 +1,29 @@
+//! Which cells around a square cell count as its neighbors.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Largest supported neighborhood range. The range-10 Moore neighborhood
+/// already has [`crate::rule::MAX_NEIGHBORS`] cells.
+pub const MAX_RANGE: usize = 10;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Neighborhood {
+    /// Every cell within `range` steps along both axes: a square.
+    #[default]
+    Moore,
+    /// Every cell within `range` orthogonal steps: a diamond.
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// How many cells to either side the neighborhood covers in the row
+    /// `dy` rows away from the center.
+    pub fn reach(self, range: usize, dy: isize) -> usize {
+        match self {
+            Neighborhood::Moore => range,
+            Neighborhood::VonNeumann => range - dy.unsigned_abs(),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Editing one board together over WebSocket, behind the `multiplayer`
+//! feature.
+//!
+//! `--host <ADDR>` listens for players and owns the board: it runs the
+//! simulation as usual and sends the whole board to everyone whenever it
+//! changes. `--join <ADDR>` connects to a host, stops stepping locally and
+//! sends each cell it changes by hand back as a `set-cell` message.
+//!
+//! Messages are JSON text frames, tagged by `type`.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use tungstenite::handshake::server::{NoCallback, ServerHandshake};
+use tungstenite::handshake::MidHandshake;
+use tungstenite::{HandshakeError, WebSocket};
+
+use crate::board::Board;
+use crate::config::Settings;
+use crate::panel::BoardResized;
+use crate::{GameData, SetCellEvent, SimulationTick};
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Message {
+    /// The host's board, as the positions of its live cells.
+    Board {
+        width: usize,
+        height: usize,
+        generation: u64,
+        alive: Vec<[usize; 2]>,
+    },
+    /// A player turning one cell on or off.
+    SetCell { x: usize, y: usize, alive: bool },
+}
+
+/// Board sides accepted from a host, the same range the settings panel
+/// allows, so a bad message can't size the board out of memory.
+const BOARD_SIDES: RangeInclusive<usize> = 8..=4096;
+
+/// The host's side of the WebSocket handshake with a new player.
+type Handshake = ServerHandshake<TcpStream, NoCallback>;
+
+enum Role {
+    Host {
+        listener: TcpListener,
+        /// Players whose handshake is waiting on more of their request.
+        joining: Vec<(SocketAddr, MidHandshake<Handshake>)>,
+        players: Vec<WebSocket<TcpStream>>,
+    },
+    Client {
+        host: WebSocket<TcpStream>,
+    },
+}
+
+/// The open connections and the board as it was last sent or received, so
+/// anything different since is news to the other side.
+#[derive(Resource)]
+pub struct Session {
+    role: Role,
+    snapshot: Option<Board>,
+}
+
+/// Opens the session asked for by `--host` or `--join`, if any.
+pub fn start_session(mut commands: Commands, settings: Res<Settings>) {
+    let role = if let Some(addr) = &settings.host {
+        TcpListener::bind(addr)
+            .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+            .map(|listener| {
+                info!("hosting on ws://{addr}");
+                Role::Host {
+                    listener,
+                    joining: Vec::new(),
+                    players: Vec::new(),
+                }
+            })
+            .map_err(|err| err.to_string())
+    } else if let Some(addr) = &settings.join {
+        connect(addr).map(|host| {
+            info!("joined ws://{addr}");
+            Role::Client { host }
+        })
+    } else {
+        return;
+    };
+    match role {
+        Ok(role) => commands.insert_resource(Session {
+            role,
+            snapshot: None,
+        }),
+        Err(err) => error!("failed to start the session: {err}"),
+    }
+}
+
+fn connect(addr: &str) -> Result<WebSocket<TcpStream>, String> {
+    let stream = TcpStream::connect(addr).map_err(|err| err.to_string())?;
+    let (socket, _) =
+        tungstenite::client(format!("ws://{addr}/"), stream).map_err(|err| err.to_string())?;
+    socket
+        .get_ref()
+        .set_nonblocking(true)
+        .map_err(|err| err.to_string())?;
+    Ok(socket)
+}
+
+/// Starts the handshake with a new player. The socket never blocks, so a
+/// player who is slow to send their request is resumed on later frames
+/// instead of holding up the app.
+fn accept(stream: TcpStream) -> Result<WebSocket<TcpStream>, HandshakeError<Handshake>> {
+    if let Err(err) = stream.set_nonblocking(true) {
+        return Err(HandshakeError::Failure(err.into()));
+    }
+    tungstenite::accept(stream)
+}
+
+fn is_would_block(err: &tungstenite::Error) -> bool {
+    matches!(err, tungstenite::Error::Io(err) if err.kind() == io::ErrorKind::WouldBlock)
+}
+
+/// Every message waiting on `socket`.
+fn receive(socket: &mut WebSocket<TcpStream>) -> tungstenite::Result<Vec<Message>> {
+    let mut messages = Vec::new();
+    loop {
+        match socket.read() {
+            Ok(tungstenite::Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(message) => messages.push(message),
+                Err(err) => warn!("ignoring a bad message: {err}"),
+            },
+            Ok(_) => {}
+            Err(err) if is_would_block(&err) => return Ok(messages),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Queues `message`; whatever doesn't fit in the socket now is sent on
+/// later reads and writes.
+fn send(socket: &mut WebSocket<TcpStream>, message: &Message) -> tungstenite::Result<()> {
+    let text = serde_json::to_string(message).expect("messages always serialize");
+    match socket.send(tungstenite::Message::Text(text)) {
+        Err(err) if !is_would_block(&err) => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn board_message(game_data: &GameData) -> Message {
+    let board = &game_data.board;
+    let mut alive = Vec::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if board.get(x, y) {
+                alive.push([x, y]);
+            }
+        }
+    }
+    Message::Board {
+        width: board.width(),
+        height: board.height(),
+        generation: game_data.generation,
+        alive,
+    }
+}
+
+/// Runs at the end of each frame, after every edit and step: the host
+/// takes in new players and their edits and sends out the board if it
+/// changed, and a client sends its edits and takes in the host's board.
+pub fn sync_board(
+    mut commands: Commands,
+    mut session: ResMut<Session>,
+    mut game_data: ResMut<GameData>,
+    mut settings: ResMut<Settings>,
+    mut sim_tick: ResMut<SimulationTick>,
+    mut set_cells: EventWriter<SetCellEvent>,
+    mut resized: EventWriter<BoardResized>,
+) {
+    let Session { role, snapshot } = &mut *session;
+    match role {
+        Role::Host {
+            listener,
+            joining,
+            players,
+        } => {
+            let mut handshakes: Vec<_> = joining
+                .drain(..)
+                .map(|(addr, handshake)| (addr, handshake.handshake()))
+                .collect();
+            while let Ok((stream, addr)) = listener.accept() {
+                handshakes.push((addr, accept(stream)));
+            }
+            for (addr, handshake) in handshakes {
+                match handshake {
+                    Ok(mut socket) => {
+                        info!("{addr} joined");
+                        if send(&mut socket, &board_message(&game_data)).is_ok() {
+                            players.push(socket);
+                        }
+                    }
+                    Err(HandshakeError::Interrupted(handshake)) => {
+                        joining.push((addr, handshake));
+                    }
+                    Err(HandshakeError::Failure(err)) => warn!("{addr} failed to join: {err}"),
+                }
+            }
+            players.retain_mut(|player| match receive(player) {
+                Ok(messages) => {
+                    for message in messages {
+                        if let Message::SetCell { x, y, alive } = message {
+                            set_cells.send(SetCellEvent { x, y, alive });
+                        }
+                    }
+                    true
+                }
+                Err(err) => {
+                    info!("a player left: {err}");
+                    false
+                }
+            });
+            if snapshot.as_ref() != Some(&game_data.board) {
+                let message = board_message(&game_data);
+                players.retain_mut(|player| send(player, &message).is_ok());
+                *snapshot = Some(game_data.board.clone());
+            }
+        }
+        Role::Client { host } => {
+            // The host drives the tick, so generations only arrive from it.
+            if !sim_tick.timer.paused() {
+                sim_tick.timer.pause();
+            }
+            if let Some(snapshot) = snapshot.as_mut() {
+                let board = &game_data.board;
+                for y in 0..board.height() {
+                    for x in 0..board.width() {
+                        let alive = board.get(x, y);
+                        if snapshot.get(x, y) != alive {
+                            let _ = send(host, &Message::SetCell { x, y, alive });
+                            snapshot.set(x, y, alive);
+                        }
+                    }
+                }
+            }
+            let messages = match receive(host) {
+                Ok(messages) => messages,
+                Err(err) => {
+                    error!("lost the host, carrying on alone: {err}");
+                    sim_tick.timer.unpause();
+                    commands.remove_resource::<Session>();
+                    return;
+                }
+            };
+            for message in messages {
+                let Message::Board {
+                    width,
+                    height,
+                    generation,
+                    alive,
+                } = message
+                else {
+                    continue;
+                };
+                if !BOARD_SIDES.contains(&width) || !BOARD_SIDES.contains(&height) {
+                    warn!("ignoring a {width}x{height} board from the host");
+                    continue;
+                }
+                if width != game_data.board.width() || height != game_data.board.height() {
+                    settings.width = width;
+                    settings.height = height;
+                    *game_data = GameData::new(&settings);
+                    resized.send(BoardResized);
+                }
+                game_data.board.fill(false);
+                for [x, y] in alive {
+                    if x < width && y < height {
+                        game_data.board.set(x, y, true);
+                    }
+                }
+                game_data.generation = generation;
+                *snapshot = Some(game_data.board.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! An egui side panel for changing settings at runtime, toggled with Tab.
+
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin, EguiSet};
+use rand::Rng;
+
+use crate::bindings::{Action, Keybindings};
+use crate::board::BoundaryMode;
+use crate::config::{Backend, PresentationMode, Settings};
+use crate::rule::Rule;
+use crate::texture;
+use crate::{BoardEntity, GameData, SimulationTick};
+
+/// Sent after the board is replaced by one of a different size, so
+/// everything drawn at the old size can be rebuilt.
+pub struct BoardResized;
+
+#[derive(Resource, Default)]
+pub struct SettingsPanel {
+    open: bool,
+    /// The rule being typed, applied when the field loses focus.
+    rule_text: Option<String>,
+    rule_error: Option<String>,
+    size: Option<[usize; 2]>,
+}
+
+pub struct SettingsPanelPlugin;
+
+impl Plugin for SettingsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugin(EguiPlugin);
+        }
+        // Runs before everything else reads input, so clicks and typing in
+        // the panel don't also reach the board.
+        app.init_resource::<SettingsPanel>()
+            .add_event::<BoardResized>()
+            .add_system(
+                settings_panel
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem)
+                    .after(EguiSet::BeginFrame),
+            )
+            .add_system(despawn_board_entities.run_if(on_event::<BoardResized>()));
+    }
+}
+
+fn hex(color: [u8; 3]) -> String {
+    format!("{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn rgb(color: Color) -> [u8; 3] {
+    let [r, g, b, _] = texture::rgba_u8(color);
+    [r, g, b]
+}
+
+/// Everything the panel edits.
+#[derive(SystemParam)]
+struct Editable<'w> {
+    settings: ResMut<'w, Settings>,
+    game_data: ResMut<'w, GameData>,
+    boundary: ResMut<'w, BoundaryMode>,
+    sim_tick: ResMut<'w, SimulationTick>,
+    clear_color: ResMut<'w, ClearColor>,
+}
+
+/// Draws the panel. Values are edited on copies and only written back when
+/// they change, so the resources don't look modified every frame.
+fn settings_panel(
+    mut contexts: EguiContexts,
+    mut panel: ResMut<SettingsPanel>,
+    editable: Editable,
+    mut resized: EventWriter<BoardResized>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    bindings: Res<Keybindings>,
+) {
+    let Editable {
+        mut settings,
+        mut game_data,
+        mut boundary,
+        mut sim_tick,
+        mut clear_color,
+    } = editable;
+    let panel = &mut *panel;
+    let ctx = contexts.ctx_mut();
+    let toggle = bindings.just_pressed(Action::SettingsPanel, &keyboard, &mouse);
+    if toggle && !ctx.wants_keyboard_input() {
+        panel.open = !panel.open;
+    }
+    if !panel.open {
+        return;
+    }
+
+    egui::SidePanel::left("settings").show(ctx, |ui| {
+        ui.heading("Settings");
+
+        let mut tick = settings.tick;
+        let slider = egui::Slider::new(&mut tick, 1..=2000)
+            .logarithmic(true)
+            .text("tick (ms)");
+        if ui.add(slider).changed() {
+            settings.tick = tick;
+            sim_tick.timer.set_duration(Duration::from_millis(tick));
+        }
+
+        let current_rule = game_data.rule.to_string();
+        let rule_text = panel.rule_text.get_or_insert(current_rule);
+        let response = ui.horizontal(|ui| {
+            ui.label("rule");
+            ui.text_edit_singleline(rule_text)
+        });
+        if response.inner.lost_focus() {
+            let parsed = rule_text.parse::<Rule>().and_then(|rule| {
+                let gpu_ready = rule.is_classic() && rule.states <= 2;
+                if settings.backend == Backend::Gpu && !gpu_ready {
+                    Err(String::from(
+                        "the GPU backend only runs two-state Moore rules",
+                    ))
+                } else {
+                    Ok(rule)
+                }
+            });
+            panel.rule_text = None;
+            match parsed {
+                Ok(rule) => {
+                    game_data.rule = rule;
+                    settings.rule = rule;
+                    panel.rule_error = None;
+                }
+                Err(err) => panel.rule_error = Some(err),
+            }
+        }
+        if let Some(err) = &panel.rule_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, err);
+        }
+
+        let mut mode = *boundary;
+        egui::ComboBox::from_label("boundary")
+            .selected_text(format!("{mode:?}"))
+            .show_ui(ui, |ui| {
+                for option in [
+                    BoundaryMode::Torus,
+                    BoundaryMode::DeadBorder,
+                    BoundaryMode::Mirror,
+                ] {
+                    ui.selectable_value(&mut mode, option, format!("{option:?}"));
+                }
+            });
+        if mode != *boundary {
+            *boundary = mode;
+            settings.boundary = mode;
+        }
+
+        let mut present_mode = settings.present_mode;
+        egui::ComboBox::from_label("present mode")
+            .selected_text(format!("{present_mode:?}"))
+            .show_ui(ui, |ui| {
+                for option in [
+                    PresentationMode::AutoVsync,
+                    PresentationMode::Immediate,
+                    PresentationMode::Mailbox,
+                ] {
+                    ui.selectable_value(&mut present_mode, option, format!("{option:?}"));
+                }
+            });
+        if present_mode != settings.present_mode {
+            settings.present_mode = present_mode;
+        }
+
+        let mut density = settings.density;
+        if ui
+            .add(egui::Slider::new(&mut density, 0.0..=1.0).text("fill density"))
+            .changed()
+        {
+            settings.density = density;
+        }
+        if ui.button("New soup").clicked() {
+            let seed = rand::thread_rng().gen();
+            game_data.reseed(seed, settings.density);
+            info!("random soup seed: {seed}");
+        }
+
+        ui.separator();
+        let mut background = rgb(settings.background_color());
+        let mut cells = rgb(settings.cell_color());
+        ui.horizontal(|ui| {
+            ui.label("background");
+            if ui.color_edit_button_srgb(&mut background).changed() {
+                settings.background_color = hex(background);
+                settings.theme = None;
+                clear_color.0 = settings.background_color();
+            }
+            ui.label("cells");
+            if ui.color_edit_button_srgb(&mut cells).changed() {
+                settings.cell_color = hex(cells);
+                settings.theme = None;
+            }
+        });
+
+        ui.separator();
+        let [width, height] = panel.size.get_or_insert([settings.width, settings.height]);
+        ui.horizontal(|ui| {
+            ui.label("size");
+            ui.add(egui::DragValue::new(width).clamp_range(8..=4096));
+            ui.label("×");
+            ui.add(egui::DragValue::new(height).clamp_range(8..=4096));
+        });
+        let (width, height) = (*width, *height);
+        ui.horizontal(|ui| {
+            // The GPU board images are only created at startup.
+            let resizable = settings.backend == Backend::Cpu;
+            if ui
+                .add_enabled(resizable, egui::Button::new("Resize"))
+                .clicked()
+            {
+                settings.width = width;
+                settings.height = height;
+                *game_data = GameData::new(&settings);
+                resized.send(BoardResized);
+            }
+            if ui.button("Reset").clicked() {
+                *game_data = GameData::new(&settings);
+            }
+        });
+    });
+
+    if ctx.wants_keyboard_input() {
+        keyboard.clear();
+    }
+    if ctx.is_pointer_over_area() || ctx.wants_pointer_input() {
+        for action in [Action::Draw, Action::Select] {
+            bindings.release(action, &mut keyboard, &mut mouse);
+        }
+    }
+}
+
+/// Clears away everything sized to the old board. The startup systems that
+/// built it run again on [`BoardResized`] to replace it.
+fn despawn_board_entities(mut commands: Commands, entities: Query<Entity, With<BoardEntity>>) {
+    for entity in &entities {
+        commands.entity(entity).despawn_recursive();
+    }
+}
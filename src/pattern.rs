@@ -0,0 +1,275 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::board::Board;
+use crate::macrocell;
+
+/// The longest run, row or column [`Pattern::parse_rle`] accepts.
+const MAX_RLE_SIDE: usize = 1 << 16;
+/// The most cells [`Pattern::parse_rle`] accepts, counting dead ones.
+const MAX_RLE_CELLS: usize = 1 << 24;
+
+/// A rectangular block of cells stored row by row from the top down, the
+/// same order pattern files use.
+#[derive(Clone, Default)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<bool>,
+}
+
+impl Pattern {
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.width + x]
+    }
+
+    /// Copies the inclusive board rectangle `min..=max`.
+    pub fn from_region(board: &Board, min: [usize; 2], max: [usize; 2]) -> Self {
+        Pattern {
+            width: max[0] - min[0] + 1,
+            height: max[1] - min[1] + 1,
+            cells: (min[1]..=max[1])
+                .rev()
+                .flat_map(|y| (min[0]..=max[0]).map(move |x| board.get(x, y)))
+                .collect(),
+        }
+    }
+
+    /// Writes the pattern onto the board with its top-left cell at `top_left`,
+    /// dropping anything that falls off the edge.
+    pub fn stamp(&self, board: &mut Board, top_left: [usize; 2]) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let x = top_left[0] as isize + col as isize;
+                let y = top_left[1] as isize - row as isize;
+                if let Some([x, y]) = board.checked(x, y) {
+                    board.set(x, y, self.get(col, row));
+                }
+            }
+        }
+    }
+
+    /// The pattern turned a quarter turn clockwise.
+    pub fn rotated_clockwise(&self) -> Self {
+        Pattern {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|row| (0..self.height).map(move |col| (row, col)))
+                .map(|(row, col)| self.get(row, self.height - 1 - col))
+                .collect(),
+        }
+    }
+
+    /// The pattern mirrored left to right.
+    pub fn flipped_horizontally(&self) -> Self {
+        Pattern {
+            cells: (0..self.height)
+                .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+                .map(|(row, col)| self.get(self.width - 1 - col, row))
+                .collect(),
+            ..*self
+        }
+    }
+
+    /// The pattern mirrored top to bottom.
+    pub fn flipped_vertically(&self) -> Self {
+        Pattern {
+            cells: (0..self.height)
+                .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+                .map(|(row, col)| self.get(col, self.height - 1 - row))
+                .collect(),
+            ..*self
+        }
+    }
+
+    /// Stamps the pattern so that it is centered on the board.
+    pub fn stamp_centered(&self, board: &mut Board) {
+        let left = board.width().saturating_sub(self.width) / 2;
+        let top = (board.height() + self.height) / 2;
+        self.stamp(board, [left, top.min(board.height() - 1)]);
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let has_extension = |name: &str| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(name))
+        };
+        let pattern = if has_extension("rle") {
+            Self::parse_rle(&text)
+        } else if has_extension("mc") {
+            macrocell::parse(&text)
+        } else {
+            Self::parse_plaintext(&text)
+        };
+        pattern.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn from_rows(rows: Vec<Vec<bool>>) -> Self {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+        let cells = rows
+            .into_iter()
+            .flat_map(|mut row| {
+                row.resize(width, false);
+                row
+            })
+            .collect();
+        Pattern {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses text of unknown format, such as a pasted pattern. RLE is
+    /// recognised by its `x = ...` header or closing `!`; anything else has
+    /// to be plaintext.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let is_rle = text.ends_with('!')
+            || text
+                .lines()
+                .any(|line| line.starts_with("x ") || line.starts_with("x="));
+        if is_rle {
+            return Self::parse_rle(text);
+        }
+        let is_plaintext = text
+            .lines()
+            .filter(|line| !line.starts_with('!'))
+            .all(|line| {
+                line.chars()
+                    .all(|c| matches!(c, '.' | 'O' | '*') || c.is_whitespace())
+            });
+        if is_plaintext {
+            Self::parse_plaintext(text)
+        } else {
+            Err(String::from("not an RLE or plaintext pattern"))
+        }
+    }
+
+    /// Parses the `.cells` plaintext format: `!` comments, `.` dead, `O` alive.
+    pub fn parse_plaintext(text: &str) -> Result<Self, String> {
+        let rows = text
+            .lines()
+            .filter(|line| !line.starts_with('!'))
+            .map(|line| {
+                line.trim_end()
+                    .chars()
+                    .map(|c| c == 'O' || c == '*')
+                    .collect()
+            })
+            .collect();
+        Ok(Self::from_rows(rows))
+    }
+
+    /// Writes the pattern as RLE, wrapping lines at 70 characters as the
+    /// format asks. Dead cells are written out to the edges so the pattern
+    /// parses back at the same size.
+    pub fn to_rle(&self) -> String {
+        let mut runs: Vec<(usize, char)> = Vec::new();
+        let mut push = |count: usize, tag: char| match runs.last_mut() {
+            Some((last, last_tag)) if *last_tag == tag => *last += count,
+            _ => runs.push((count, tag)),
+        };
+        for row in 0..self.height {
+            if row > 0 {
+                push(1, '$');
+            }
+            for col in 0..self.width {
+                push(1, if self.get(col, row) { 'o' } else { 'b' });
+            }
+        }
+        let mut text = format!("x = {}, y = {}\n", self.width, self.height);
+        let mut line = 0;
+        for (count, tag) in runs.into_iter().chain([(1, '!')]) {
+            let run = if count > 1 {
+                format!("{count}{tag}")
+            } else {
+                tag.to_string()
+            };
+            if line + run.len() > 70 {
+                text.push('\n');
+                line = 0;
+            }
+            line += run.len();
+            text.push_str(&run);
+        }
+        text.push('\n');
+        text
+    }
+
+    /// Parses run-length encoded patterns as used by Golly and LifeWiki.
+    /// Patterns with a side over [`MAX_RLE_SIDE`] or more than
+    /// [`MAX_RLE_CELLS`] cells are refused.
+    pub fn parse_rle(text: &str) -> Result<Self, String> {
+        let too_large = || String::from("RLE pattern is too large");
+        let mut rows = vec![Vec::new()];
+        let mut width = 0;
+        let mut run = 0usize;
+        'lines: for line in text.lines() {
+            let line = line.trim();
+            if line.starts_with('#') || line.starts_with("x ") || line.starts_with("x=") {
+                continue;
+            }
+            for c in line.chars() {
+                let count = run.max(1);
+                match c {
+                    '0'..='9' => {
+                        run = run
+                            .checked_mul(10)
+                            .and_then(|run| run.checked_add(c.to_digit(10).unwrap() as usize))
+                            .filter(|&run| run <= MAX_RLE_SIDE)
+                            .ok_or_else(too_large)?;
+                        continue;
+                    }
+                    '$' => rows.extend(std::iter::repeat_with(Vec::new).take(count)),
+                    '!' => break 'lines,
+                    c if c.is_ascii_alphabetic() || c == '.' => {
+                        let row = rows.last_mut().unwrap();
+                        let alive = c != 'b' && c != '.';
+                        row.extend(std::iter::repeat_n(alive, count));
+                        width = width.max(row.len());
+                    }
+                    c if c.is_whitespace() => continue,
+                    _ => return Err(format!("unexpected `{c}` in RLE data")),
+                }
+                let height = rows.len();
+                if width > MAX_RLE_SIDE
+                    || height > MAX_RLE_SIDE
+                    || width.saturating_mul(height) > MAX_RLE_CELLS
+                {
+                    return Err(too_large());
+                }
+                run = 0;
+            }
+        }
+        Ok(Self::from_rows(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rle_round_trip() {
+        let glider = Pattern::parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        let mut board = Board::new(20, 20);
+        glider.stamp(&mut board, [2, 17]);
+        board.set(15, 3, true);
+        let pattern = Pattern::from_region(&board, [0, 0], [19, 19]);
+        let parsed = Pattern::parse(&pattern.to_rle()).unwrap();
+        assert_eq!([parsed.width, parsed.height], [20, 20]);
+        assert_eq!(parsed.cells, pattern.cells);
+    }
+
+    #[test]
+    fn rle_refuses_huge_counts() {
+        assert!(Pattern::parse_rle("99999999999999999999999o!").is_err());
+        assert!(Pattern::parse_rle("4096o$4096$4096o!").is_err());
+        assert!(Pattern::parse_rle("65537$o!").is_err());
+    }
+}
@@ -0,0 +1,255 @@
+//! Noticing when the board has settled into a still life, an oscillator, or
+//! a spaceship.
+//!
+//! Every generation the live cells are hashed relative to their bounding
+//! box. A hash seen again with the box in the same place means the board is
+//! repeating in place; in a different place, it is repeating while moving.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::board::Board;
+use crate::config::{Automaton, Settings};
+use crate::{GameData, SimulationTick};
+
+/// Longest period looked for.
+const MAX_PERIOD: usize = 128;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Periodicity {
+    StillLife,
+    Oscillator { period: u64 },
+    Spaceship { period: u64, dx: isize, dy: isize },
+}
+
+impl Periodicity {
+    /// What a board that matches itself `period` generations ago, moved by
+    /// `[dx, dy]`, is.
+    pub fn from_repeat(period: u64, dx: isize, dy: isize) -> Self {
+        match (period, dx, dy) {
+            (1, 0, 0) => Periodicity::StillLife,
+            (period, 0, 0) => Periodicity::Oscillator { period },
+            (period, dx, dy) => Periodicity::Spaceship { period, dx, dy },
+        }
+    }
+}
+
+impl fmt::Display for Periodicity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Periodicity::StillLife => write!(f, "still life"),
+            Periodicity::Oscillator { period } => write!(f, "oscillator, period {period}"),
+            Periodicity::Spaceship { period, dx, dy } => {
+                write!(f, "spaceship, ({dx}, {dy}) every {period} generations")
+            }
+        }
+    }
+}
+
+struct Snapshot {
+    generation: u64,
+    hash: u64,
+    origin: (usize, usize),
+}
+
+/// Recent board hashes and what they add up to.
+#[derive(Resource, Default)]
+pub struct PeriodDetector {
+    history: VecDeque<Snapshot>,
+    current: Option<Periodicity>,
+    /// The generation `current` was first seen at.
+    found_at: u64,
+}
+
+/// The live cells' hash relative to their bounding box, and the box's
+/// bottom-left corner. `None` for an empty board.
+pub fn snapshot(board: &Board) -> Option<(u64, (usize, usize))> {
+    let mut live = Vec::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if board.get(x, y) {
+                live.push((x, y));
+            }
+        }
+    }
+    let min_x = live.iter().map(|&(x, _)| x).min()?;
+    let min_y = live.first()?.1;
+    let mut hasher = DefaultHasher::new();
+    for &(x, y) in &live {
+        (x - min_x, y - min_y).hash(&mut hasher);
+    }
+    Some((hasher.finish(), (min_x, min_y)))
+}
+
+impl PeriodDetector {
+    /// Hashes the board if it is at a new generation and checks it against
+    /// the recent ones.
+    pub fn record(&mut self, game_data: &GameData) {
+        if game_data.automaton != Automaton::Life {
+            return;
+        }
+        let generation = game_data.generation;
+        match self.history.back() {
+            Some(last) if last.generation == generation => return,
+            // The board was restarted or rewound.
+            Some(last) if last.generation > generation => self.history.clear(),
+            _ => {}
+        }
+
+        let found = snapshot(&game_data.board).and_then(|(hash, origin)| {
+            let repeat = self
+                .history
+                .iter()
+                .rev()
+                .find(|snapshot| snapshot.hash == hash)
+                .map(|previous| {
+                    let period = generation - previous.generation;
+                    let dx = origin.0 as isize - previous.origin.0 as isize;
+                    let dy = origin.1 as isize - previous.origin.1 as isize;
+                    Periodicity::from_repeat(period, dx, dy)
+                });
+            self.history.push_back(Snapshot {
+                generation,
+                hash,
+                origin,
+            });
+            repeat
+        });
+        if self.history.len() > MAX_PERIOD {
+            self.history.pop_front();
+        }
+        if found != self.current {
+            self.current = found;
+            self.found_at = generation;
+        }
+    }
+}
+
+/// Checks a generation stepped outside the simulation tick, which records
+/// its own, and reports a newly found repeat in the title bar, pausing if
+/// `auto_pause` is set.
+pub fn detect_periodicity(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    mut detector: ResMut<PeriodDetector>,
+    mut sim_tick: ResMut<SimulationTick>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut base_title: Local<Option<String>>,
+    mut shown: Local<Option<Periodicity>>,
+) {
+    detector.record(&game_data);
+    let found = detector.current;
+    if found == *shown {
+        return;
+    }
+
+    *shown = found;
+    if let Some(periodicity) = found {
+        info!("generation {}: {periodicity}", detector.found_at);
+        if settings.auto_pause && !sim_tick.timer.paused() {
+            sim_tick.toggle_pause();
+        }
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let base = base_title.get_or_insert_with(|| window.title.clone());
+    window.title = match found {
+        Some(periodicity) => format!("{base} - {periodicity}"),
+        None => base.clone(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::board::BoundaryMode;
+    use crate::topology::GridTopology;
+    use crate::{export, heatmap, history, population, stats};
+
+    /// A world holding what `execute_step` needs, with `cells` alive on an
+    /// otherwise empty board.
+    fn world_with(width: usize, height: usize, cells: &[(usize, usize)]) -> World {
+        let settings = Settings {
+            width,
+            height,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let mut game_data = GameData::new(&settings);
+        for &(x, y) in cells {
+            game_data.board.set(x, y, true);
+        }
+        let mut world = World::new();
+        world.insert_resource(game_data);
+        world.insert_resource(settings);
+        world.insert_resource(BoundaryMode::DeadBorder);
+        world.insert_resource(GridTopology::Square);
+        world.insert_resource(SimulationTick {
+            timer: Timer::new(Duration::from_millis(50), TimerMode::Repeating),
+        });
+        world.init_resource::<population::PopulationHistory>();
+        world.init_resource::<history::History>();
+        world.init_resource::<export::AnimationCapture>();
+        world.init_resource::<heatmap::Heatmap>();
+        world.init_resource::<stats::StatsLog>();
+        world.init_resource::<PeriodDetector>();
+        world
+    }
+
+    /// Runs `frames` frames in each of which `steps` generations come due.
+    fn run(world: &mut World, frames: u32, steps: u32) {
+        let mut schedule = Schedule::new();
+        schedule.add_system(crate::execute_step);
+        for _ in 0..frames {
+            let mut sim_tick = world.resource_mut::<SimulationTick>();
+            let interval = sim_tick.timer.duration();
+            sim_tick.timer.tick(interval * steps);
+            schedule.run(world);
+        }
+    }
+
+    #[test]
+    fn block_is_a_still_life_at_several_steps_per_frame() {
+        let mut world = world_with(8, 8, &[(3, 3), (4, 3), (3, 4), (4, 4)]);
+        run(&mut world, 3, 3);
+        assert_eq!(world.resource::<GameData>().generation, 9);
+        assert_eq!(
+            world.resource::<PeriodDetector>().current,
+            Some(Periodicity::StillLife)
+        );
+    }
+
+    #[test]
+    fn blinker_has_period_two_at_two_steps_per_frame() {
+        let mut world = world_with(8, 8, &[(2, 4), (3, 4), (4, 4)]);
+        run(&mut world, 4, 2);
+        assert_eq!(
+            world.resource::<PeriodDetector>().current,
+            Some(Periodicity::Oscillator { period: 2 })
+        );
+    }
+
+    #[test]
+    fn glider_has_period_four_at_three_steps_per_frame() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let cells: Vec<_> = glider.iter().map(|&(x, y)| (x + 2, 20 - y)).collect();
+        let mut world = world_with(24, 24, &cells);
+        run(&mut world, 3, 3);
+        assert_eq!(
+            world.resource::<PeriodDetector>().current,
+            Some(Periodicity::Spaceship {
+                period: 4,
+                dx: 1,
+                dy: -1
+            })
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! A small chart of the live population over recent generations.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::bindings::{Action, Actions};
+use crate::config::Settings;
+use crate::texture;
+
+/// Generations kept in the graph.
+const HISTORY_LEN: usize = 4096;
+const GRAPH_WIDTH: usize = 256;
+const GRAPH_HEIGHT: usize = 64;
+const GRAPH_BACKGROUND: [u8; 4] = [0, 0, 0, 160];
+
+/// Live-cell counts of the most recent generations, oldest first.
+#[derive(Resource, Default)]
+pub struct PopulationHistory {
+    samples: VecDeque<usize>,
+}
+
+impl PopulationHistory {
+    pub fn record(&mut self, population: usize) {
+        if self.samples.len() == HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(population);
+    }
+}
+
+#[derive(Resource)]
+pub struct PopulationGraph(Handle<Image>);
+
+#[derive(Component)]
+pub struct PopulationGraphNode;
+
+pub fn spawn_population_graph(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: GRAPH_WIDTH as u32,
+            height: GRAPH_HEIGHT as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &GRAPH_BACKGROUND,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    let handle = images.add(image);
+
+    commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(8.0),
+                    right: Val::Px(8.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Px(GRAPH_WIDTH as f32), Val::Px(GRAPH_HEIGHT as f32)),
+                ..Default::default()
+            },
+            image: UiImage {
+                texture: handle.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        PopulationGraphNode,
+    ));
+    commands.insert_resource(PopulationGraph(handle));
+}
+
+/// Redraws the chart, one column per slice of the history, scaled so the
+/// largest population in view reaches the top.
+pub fn draw_population_graph(
+    history: Res<PopulationHistory>,
+    settings: Res<Settings>,
+    graph: Res<PopulationGraph>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !history.is_changed() || history.samples.is_empty() {
+        return;
+    }
+    let Some(image) = images.get_mut(&graph.0) else {
+        return;
+    };
+    let samples = &history.samples;
+    let peak = samples.iter().copied().max().unwrap_or(0).max(1);
+    let line = texture::rgba_u8(settings.cell_color());
+    for x in 0..GRAPH_WIDTH {
+        let start = x * samples.len() / GRAPH_WIDTH;
+        let end = ((x + 1) * samples.len() / GRAPH_WIDTH).max(start + 1);
+        let value = samples.range(start..end).max().copied().unwrap_or(0);
+        let bar = value * (GRAPH_HEIGHT - 1) / peak + 1;
+        for y in 0..GRAPH_HEIGHT {
+            let offset = (y * GRAPH_WIDTH + x) * 4;
+            let pixel = if GRAPH_HEIGHT - y <= bar {
+                line
+            } else {
+                GRAPH_BACKGROUND
+            };
+            image.data[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+}
+
+/// P shows or hides the chart.
+pub fn toggle_population_graph(
+    actions: Actions,
+    mut graphs: Query<&mut Visibility, With<PopulationGraphNode>>,
+) {
+    if !actions.just_pressed(Action::PopulationGraph) {
+        return;
+    }
+    for mut visibility in &mut graphs {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
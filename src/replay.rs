@@ -0,0 +1,363 @@
+//! Recording every edit made to the board and playing it back.
+//!
+//! A replay stores the seed, automaton, rule, boundary and topology the
+//! session started from and, for each generation, the cells the user turned
+//! on or off before it was stepped along with any change of boundary. Since
+//! stepping is deterministic, that is enough to rebuild the whole session. F5 starts a recording, which restarts the board from its seed,
+//! and F5 again writes it to `replay.json`; `--replay <path>` plays one
+//! back.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bindings::{Action, Actions};
+use crate::board::{Board, BoundaryMode};
+use crate::config::{Automaton, Settings};
+use crate::topology::GridTopology;
+use crate::GameData;
+
+const DEFAULT_REPLAY_PATH: &str = "replay.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct ReplayFile {
+    width: usize,
+    height: usize,
+    seed: u64,
+    density: f64,
+    rule: String,
+    #[serde(default)]
+    automaton: Automaton,
+    #[serde(default)]
+    boundary: BoundaryMode,
+    #[serde(default)]
+    topology: GridTopology,
+    edits: Vec<Edit>,
+}
+
+/// The cells changed by hand before `generation` was stepped, and the
+/// boundary mode if that was changed.
+#[derive(Serialize, Deserialize)]
+pub struct Edit {
+    generation: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    born: Vec<[usize; 2]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    died: Vec<[usize; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    boundary: Option<BoundaryMode>,
+}
+
+pub enum Replay {
+    /// The file being built, and the board and boundary as the simulation
+    /// last left them, so anything different since then is an edit.
+    Recording {
+        file: ReplayFile,
+        snapshot: Board,
+        boundary: BoundaryMode,
+    },
+    /// The edits still to come, and the boundary and topology the steps
+    /// run with in place of the app's.
+    Playing {
+        edits: VecDeque<Edit>,
+        boundary: BoundaryMode,
+        topology: GridTopology,
+    },
+}
+
+/// Restarts the board as a replay begins: the seed's random soup, or an
+/// empty board without a density.
+fn restart(game_data: &mut GameData, seed: u64, density: f64) {
+    if density > 0.0 {
+        game_data.reseed(seed, density);
+    } else {
+        game_data.board.fill(false);
+        game_data.generation = 0;
+    }
+}
+
+/// Sets up `game_data` to play back the replay at `path`.
+pub fn start_playback(game_data: &mut GameData, path: &Path) -> io::Result<()> {
+    let file: ReplayFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    if file.width != width || file.height != height {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "replay is {}x{} but the board is {}x{}",
+                file.width, file.height, width, height
+            ),
+        ));
+    }
+    let outside = file
+        .edits
+        .iter()
+        .flat_map(|edit| edit.born.iter().chain(&edit.died))
+        .find(|&&[x, y]| x >= width || y >= height);
+    if let Some([x, y]) = outside {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("replay edits cell ({x}, {y}), outside the {width}x{height} board"),
+        ));
+    }
+    game_data.rule = file
+        .rule
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if file.automaton == Automaton::Script && game_data.script.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "replay runs a rule script, which has to be given with --script",
+        ));
+    }
+    game_data.automaton = file.automaton;
+    restart(game_data, file.seed, file.density);
+    game_data.replay = Some(Replay::Playing {
+        edits: file.edits.into(),
+        boundary: file.boundary,
+        topology: file.topology,
+    });
+    Ok(())
+}
+
+/// While recording, notes every cell that differs from the last snapshot,
+/// and `boundary` if it differs from the last one, as an edit to the
+/// current generation.
+fn capture_edits(game_data: &mut GameData, boundary: BoundaryMode) {
+    let generation = game_data.generation;
+    let board = &game_data.board;
+    let Some(Replay::Recording {
+        file,
+        snapshot,
+        boundary: recorded,
+    }) = &mut game_data.replay
+    else {
+        return;
+    };
+    let mut edit = Edit {
+        generation,
+        born: Vec::new(),
+        died: Vec::new(),
+        boundary: (boundary != *recorded).then_some(boundary),
+    };
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            match (snapshot.get(x, y), board.get(x, y)) {
+                (false, true) => edit.born.push([x, y]),
+                (true, false) => edit.died.push([x, y]),
+                _ => {}
+            }
+        }
+    }
+    if edit.born.is_empty() && edit.died.is_empty() && edit.boundary.is_none() {
+        return;
+    }
+    *snapshot = board.clone();
+    *recorded = boundary;
+    match file.edits.last_mut() {
+        Some(last) if last.generation == generation => {
+            last.born.extend(edit.born);
+            last.died.extend(edit.died);
+            last.boundary = edit.boundary.or(last.boundary);
+        }
+        _ => file.edits.push(edit),
+    }
+}
+
+/// Called before each step: records the edits made since the last one, or
+/// applies the recorded ones when playing back. Returns the boundary and
+/// topology to step with, which are the replay's while it plays back.
+pub fn before_step(
+    game_data: &mut GameData,
+    boundary: BoundaryMode,
+    topology: GridTopology,
+) -> (BoundaryMode, GridTopology) {
+    capture_edits(game_data, boundary);
+    let generation = game_data.generation;
+    let Some(Replay::Playing {
+        edits,
+        boundary: played,
+        topology: played_topology,
+    }) = &mut game_data.replay
+    else {
+        return (boundary, topology);
+    };
+    while edits
+        .front()
+        .is_some_and(|edit| edit.generation <= generation)
+    {
+        let edit = edits.pop_front().unwrap();
+        for [x, y] in edit.born {
+            game_data.board.set(x, y, true);
+        }
+        for [x, y] in edit.died {
+            game_data.board.set(x, y, false);
+        }
+        if let Some(boundary) = edit.boundary {
+            *played = boundary;
+        }
+        if edits.is_empty() {
+            info!("replay finished");
+        }
+    }
+    (*played, *played_topology)
+}
+
+/// Called after each step so the step itself is not taken for an edit.
+pub fn after_step(game_data: &mut GameData) {
+    if let Some(Replay::Recording { snapshot, .. }) = &mut game_data.replay {
+        *snapshot = game_data.board.clone();
+    }
+}
+
+/// Records edits made after the frame's step, without marking the board as
+/// changed.
+pub fn capture_frame_edits(mut game_data: ResMut<GameData>, boundary: Res<BoundaryMode>) {
+    capture_edits(game_data.bypass_change_detection(), *boundary);
+}
+
+/// While playing back, keeps the boundary and topology resources on the
+/// replay's, so the rest of the app shows what the steps use.
+pub fn follow_playback(
+    game_data: Res<GameData>,
+    mut boundary: ResMut<BoundaryMode>,
+    mut topology: ResMut<GridTopology>,
+) {
+    if let Some(Replay::Playing {
+        boundary: played,
+        topology: played_topology,
+        ..
+    }) = &game_data.replay
+    {
+        boundary.set_if_neq(*played);
+        topology.set_if_neq(*played_topology);
+    }
+}
+
+/// F5 starts recording from the board's seed, and stops and saves the
+/// recording when pressed again.
+pub fn toggle_recording(
+    actions: Actions,
+    settings: Res<Settings>,
+    boundary: Res<BoundaryMode>,
+    topology: Res<GridTopology>,
+    mut game_data: ResMut<GameData>,
+) {
+    if !actions.just_pressed(Action::RecordReplay) {
+        return;
+    }
+    if let Some(Replay::Recording { .. }) = game_data.replay {
+        let Some(file) = stop_recording(&mut game_data, *boundary) else {
+            return;
+        };
+        match save(Path::new(DEFAULT_REPLAY_PATH), &file) {
+            Ok(()) => info!("saved {DEFAULT_REPLAY_PATH}"),
+            Err(err) => error!("failed to save {DEFAULT_REPLAY_PATH}: {err}"),
+        }
+        return;
+    }
+    start_recording(&mut game_data, settings.density, *boundary, *topology);
+    info!("recording a replay");
+}
+
+/// Restarts the board from its seed and starts recording.
+fn start_recording(
+    game_data: &mut GameData,
+    density: f64,
+    boundary: BoundaryMode,
+    topology: GridTopology,
+) {
+    let seed = game_data.seed;
+    restart(game_data, seed, density);
+    let file = ReplayFile {
+        width: game_data.board.width(),
+        height: game_data.board.height(),
+        seed,
+        density,
+        rule: game_data.rule.to_string(),
+        automaton: game_data.automaton,
+        boundary,
+        topology,
+        edits: Vec::new(),
+    };
+    let snapshot = game_data.board.clone();
+    game_data.replay = Some(Replay::Recording {
+        file,
+        snapshot,
+        boundary,
+    });
+}
+
+/// Takes the last edits and the finished file, if recording.
+fn stop_recording(game_data: &mut GameData, boundary: BoundaryMode) -> Option<ReplayFile> {
+    capture_edits(game_data, boundary);
+    match game_data.replay.take() {
+        Some(Replay::Recording { file, .. }) => Some(file),
+        replay => {
+            game_data.replay = replay;
+            None
+        }
+    }
+}
+
+fn save(path: &Path, file: &ReplayFile) -> io::Result<()> {
+    fs::write(path, serde_json::to_string(file)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playback_rebuilds_the_recorded_session() {
+        let settings = Settings {
+            width: 16,
+            height: 12,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let mut next_board = Board::default();
+        let mut recorded = GameData::new(&settings);
+        start_recording(
+            &mut recorded,
+            settings.density,
+            BoundaryMode::Torus,
+            GridTopology::Square,
+        );
+        for generation in 0..12 {
+            recorded.board.set(generation, 0, true);
+            recorded.board.set(15, generation, false);
+            let boundary = if generation < 6 {
+                BoundaryMode::Torus
+            } else {
+                BoundaryMode::DeadBorder
+            };
+            recorded.step(&mut next_board, boundary, GridTopology::Square);
+        }
+        let file = stop_recording(&mut recorded, BoundaryMode::DeadBorder).unwrap();
+        assert!(file.edits.iter().any(|edit| edit.boundary.is_some()));
+
+        let path = std::env::temp_dir().join(format!("replay-test-{}.json", std::process::id()));
+        save(&path, &file).unwrap();
+        let mut played = GameData::new(&Settings {
+            seed: Some(1),
+            ..settings.clone()
+        });
+        let result = start_playback(&mut played, &path);
+        std::fs::remove_file(&path).unwrap();
+        result.unwrap();
+        for _ in 0..12 {
+            played.step(
+                &mut next_board,
+                BoundaryMode::Mirror,
+                GridTopology::HexPointy,
+            );
+        }
+        assert_eq!(played.generation, recorded.generation);
+        assert!(played.board == recorded.board);
+    }
+}
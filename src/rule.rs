@@ -0,0 +1,265 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::neighborhood::{Neighborhood, MAX_RANGE};
+
+/// The most live neighbors a rule can count, enough for the range-10 Moore
+/// neighborhood.
+pub const MAX_NEIGHBORS: usize = 440;
+
+type Counts = [bool; MAX_NEIGHBORS + 1];
+
+/// An outer-totalistic birth/survival rule such as Conway's `B3/S23`, along
+/// with the neighborhood it counts over.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: Counts,
+    pub survival: Counts,
+    pub neighborhood: Neighborhood,
+    /// How far the neighborhood reaches; 1 for the classic rules.
+    pub range: usize,
+    /// Number of cell states: 2 for live and dead, more for Generations
+    /// rules whose dying cells take `states - 2` generations to clear.
+    pub states: u8,
+}
+
+impl Rule {
+    pub fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        let counts = if alive { &self.survival } else { &self.birth };
+        counts.get(neighbors).copied().unwrap_or(false)
+    }
+
+    /// Whether the rule counts Conway's eight neighbors, which has a much
+    /// faster bit-parallel step.
+    pub fn is_classic(&self) -> bool {
+        self.neighborhood == Neighborhood::Moore && self.range == 1
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+}
+
+/// Parses either a run of single digits (`23`) or, for neighborhoods with
+/// more than nine cells, a comma-separated list (`2,3,10`).
+fn parse_counts(list: &str) -> Result<Counts, String> {
+    let mut counts = [false; MAX_NEIGHBORS + 1];
+    let mut set = |n: &str| match n.parse::<usize>() {
+        Ok(n) if n <= MAX_NEIGHBORS => {
+            counts[n] = true;
+            Ok(())
+        }
+        _ => Err(format!("invalid neighbor count `{n}`")),
+    };
+    if list.contains(',') {
+        for n in list.split(',') {
+            set(n.trim())?;
+        }
+    } else {
+        for c in list.chars() {
+            set(&c.to_string())?;
+        }
+    }
+    Ok(counts)
+}
+
+/// Parses Larger than Life notation such as Bosco's Rule,
+/// `R5,C0,M1,S34..58,B34..45,NM`. With `M1` the survival interval counts
+/// the cell itself. `S` and `B` may repeat to give more than one interval.
+fn parse_larger_than_life(s: &str) -> Result<Rule, String> {
+    let mut range = 1;
+    let mut states = 2;
+    let mut middle = 0;
+    let mut neighborhood = Neighborhood::Moore;
+    let mut survival = Vec::new();
+    let mut birth = Vec::new();
+    for field in s.split(',').map(str::trim) {
+        let mut chars = field.chars();
+        let key = chars.next().map(|c| c.to_ascii_uppercase());
+        let value = chars.as_str();
+        let number = |v: &str| {
+            v.parse::<usize>()
+                .map_err(|_| format!("invalid value in `{field}`"))
+        };
+        let interval = |v: &str| match v.split_once("..") {
+            Some((lo, hi)) => Ok((number(lo)?, number(hi)?)),
+            None => number(v).map(|n| (n, n)),
+        };
+        match key {
+            Some('R') => range = number(value)?,
+            Some('C') => states = parse_states(value)?,
+            Some('M') => middle = number(value)?.min(1),
+            Some('S') => survival.push(interval(value)?),
+            Some('B') => birth.push(interval(value)?),
+            Some('N') => {
+                neighborhood = match value.to_ascii_uppercase().as_str() {
+                    "M" => Neighborhood::Moore,
+                    "N" => Neighborhood::VonNeumann,
+                    _ => return Err(format!("unknown neighborhood `{field}`")),
+                }
+            }
+            _ => return Err(format!("unknown field `{field}` in rule `{s}`")),
+        }
+    }
+    if !(1..=MAX_RANGE).contains(&range) {
+        return Err(format!("range must be between 1 and {MAX_RANGE}"));
+    }
+    let counts = |intervals: &[(usize, usize)], offset: usize| {
+        let mut counts = [false; MAX_NEIGHBORS + 1];
+        for (n, count) in counts.iter_mut().enumerate() {
+            *count = intervals
+                .iter()
+                .any(|&(lo, hi)| (lo..=hi).contains(&(n + offset)));
+        }
+        counts
+    };
+    Ok(Rule {
+        birth: counts(&birth, 0),
+        survival: counts(&survival, middle),
+        neighborhood,
+        range,
+        states,
+    })
+}
+
+/// The state count of a Generations rule. `0` and `1` mean two states, as
+/// in Larger than Life's `C0`.
+fn parse_states(value: &str) -> Result<u8, String> {
+    value
+        .parse::<u8>()
+        .map(|states| states.max(2))
+        .map_err(|_| format!("invalid state count `{value}`"))
+}
+
+/// Accepts `B3/S23`, the older `23/3` (survival/birth) notation and Larger
+/// than Life's `R5,C0,M1,S34..58,B34..45,NM`. A third part gives the state
+/// count of a Generations rule, as in `B2/S/C3` or Brian's Brain's `/2/3`.
+/// A trailing `V` selects the von Neumann neighborhood. A trailing `H`, as
+/// in the hexagonal `B2/S34H`, is allowed; the lattice itself is picked with
+/// `GridTopology`.
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        if matches!(chars.next(), Some('R' | 'r'))
+            && chars.next().is_some_and(|c| c.is_ascii_digit())
+        {
+            return parse_larger_than_life(s);
+        }
+        let von_neumann = s.ends_with(['V', 'v']);
+        let mut parts = s.trim_end_matches(['H', 'h', 'V', 'v']).split('/');
+        let (Some(first), Some(second)) = (parts.next(), parts.next()) else {
+            return Err(format!("rule `{s}` is missing a `/`"));
+        };
+        let states = match parts.next() {
+            Some(states) => parse_states(states.trim_start_matches(['C', 'c']))?,
+            None => 2,
+        };
+        if parts.next().is_some() {
+            return Err(format!("rule `{s}` has too many parts"));
+        }
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B' | 'b'), _) => (&first[1..], second.trim_start_matches(['S', 's'])),
+            (Some('S' | 's'), _) => (second.trim_start_matches(['B', 'b']), &first[1..]),
+            _ => (second, first),
+        };
+        Ok(Rule {
+            birth: parse_counts(birth)?,
+            survival: parse_counts(survival)?,
+            neighborhood: if von_neumann {
+                Neighborhood::VonNeumann
+            } else {
+                Neighborhood::Moore
+            },
+            range: 1,
+            states,
+        })
+    }
+}
+
+/// Writes range-1 rules as `B3/S23` (with a `V` for von Neumann) and
+/// anything wider in Larger than Life notation.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.range > 1 {
+            let intervals = |key: char, counts: &Counts| -> Vec<String> {
+                let mut runs = Vec::new();
+                let mut n = 0;
+                while n <= MAX_NEIGHBORS {
+                    if counts[n] {
+                        let start = n;
+                        while n < MAX_NEIGHBORS && counts[n + 1] {
+                            n += 1;
+                        }
+                        runs.push(format!("{key}{start}..{n}"));
+                    }
+                    n += 1;
+                }
+                runs
+            };
+            let mut fields = vec![
+                format!("R{}", self.range),
+                format!("C{}", if self.states > 2 { self.states } else { 0 }),
+                "M0".into(),
+            ];
+            fields.extend(intervals('S', &self.survival));
+            fields.extend(intervals('B', &self.birth));
+            fields.push(match self.neighborhood {
+                Neighborhood::Moore => "NM".into(),
+                Neighborhood::VonNeumann => "NN".into(),
+            });
+            return write!(f, "{}", fields.join(","));
+        }
+        let digits = |counts: &Counts| -> String {
+            let set: Vec<String> = (0..=MAX_NEIGHBORS)
+                .filter(|&n| counts[n])
+                .map(|n| n.to_string())
+                .collect();
+            if set.iter().all(|n| n.len() == 1) {
+                set.concat()
+            } else {
+                set.join(",")
+            }
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        if self.neighborhood == Neighborhood::VonNeumann {
+            write!(f, "V")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn larger_than_life_round_trips() {
+        let rule: Rule = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+        assert_eq!(rule.range, 5);
+        assert_eq!(rule.neighborhood, Neighborhood::Moore);
+        // `M1` counts the cell itself, so survival starts one lower.
+        assert!(!rule.survival[32] && rule.survival[33] && rule.survival[57]);
+        assert!(!rule.survival[58]);
+        assert!(rule.birth[34] && rule.birth[45] && !rule.birth[46]);
+        assert_eq!(rule.to_string(), "R5,C0,M0,S33..57,B34..45,NM");
+        assert!(rule.to_string().parse::<Rule>().unwrap() == rule);
+    }
+
+    #[test]
+    fn classic_notation_round_trips() {
+        for text in ["B3/S23", "B36/S23", "B2/S34V", "B1,10/S2"] {
+            let rule: Rule = text.parse().unwrap();
+            assert_eq!(rule.range, 1);
+            assert_eq!(rule.to_string(), text);
+        }
+        assert!("23/3".parse::<Rule>().unwrap() == Rule::default());
+    }
+}
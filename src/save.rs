@@ -0,0 +1,222 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::config::Settings;
+use crate::{GameData, SimulationTick};
+
+const DEFAULT_SAVE_PATH: &str = "game_of_life.json";
+
+/// On-disk snapshot of everything needed to resume a simulation.
+/// Rows are stored bottom to top as strings of `O` (alive) and `.` (dead).
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    width: usize,
+    height: usize,
+    generation: u64,
+    #[serde(default)]
+    seed: u64,
+    rule: String,
+    tick_millis: u64,
+    paused: bool,
+    rows: Vec<String>,
+}
+
+impl SavedState {
+    fn capture(game_data: &GameData, sim_tick: &SimulationTick) -> Self {
+        SavedState {
+            width: game_data.board.width(),
+            height: game_data.board.height(),
+            generation: game_data.generation,
+            seed: game_data.seed,
+            rule: game_data.rule.to_string(),
+            tick_millis: sim_tick.timer.duration().as_millis() as u64,
+            paused: sim_tick.timer.paused(),
+            rows: (0..game_data.board.height())
+                .map(|y| {
+                    (0..game_data.board.width())
+                        .map(|x| if game_data.board.get(x, y) { 'O' } else { '.' })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    fn restore(&self, game_data: &mut GameData, sim_tick: &mut SimulationTick) -> io::Result<()> {
+        let (width, height) = (game_data.board.width(), game_data.board.height());
+        if self.width != width || self.height != height || self.rows.len() != height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "save is {}x{} but the board is {}x{}",
+                    self.width, self.height, width, height
+                ),
+            ));
+        }
+        let rule = self
+            .rule
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        // Nothing from the old board may show through, including what is
+        // kept per cell.
+        game_data.board = Board::new(width, height);
+        game_data.ages.clear();
+        game_data.decay.clear();
+        game_data.colors.clear();
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, c) in row.chars().take(width).enumerate() {
+                game_data.board.set(x, y, c == 'O');
+            }
+        }
+        game_data.generation = self.generation;
+        game_data.seed = self.seed;
+        game_data.rule = rule;
+        sim_tick
+            .timer
+            .set_duration(Duration::from_millis(self.tick_millis));
+        if self.paused {
+            sim_tick.timer.pause();
+        } else {
+            sim_tick.timer.unpause();
+        }
+        Ok(())
+    }
+}
+
+fn save_to_file(path: &Path, game_data: &GameData, sim_tick: &SimulationTick) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(&SavedState::capture(game_data, sim_tick))?;
+    fs::write(path, json)
+}
+
+fn load_from_file(
+    path: &Path,
+    game_data: &mut GameData,
+    sim_tick: &mut SimulationTick,
+) -> io::Result<()> {
+    let state: SavedState = serde_json::from_str(&fs::read_to_string(path)?)?;
+    state.restore(game_data, sim_tick)
+}
+
+/// A new board built from `settings` at the size of the save at `path`,
+/// with the save loaded onto it.
+pub fn load_board(
+    path: &Path,
+    settings: &Settings,
+    sim_tick: &mut SimulationTick,
+) -> io::Result<GameData> {
+    let state: SavedState = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut game_data = GameData::new(&Settings {
+        width: state.width,
+        height: state.height,
+        ..settings.clone()
+    });
+    state.restore(&mut game_data, sim_tick)?;
+    Ok(game_data)
+}
+
+/// Loads the file given with `--load <path>` on startup, if any, resizing
+/// the board to match it. This runs before anything is drawn at the size
+/// of the board.
+pub fn load_from_args(
+    mut settings: ResMut<Settings>,
+    mut game_data: ResMut<GameData>,
+    mut sim_tick: ResMut<SimulationTick>,
+) {
+    let Some(path) = settings.load.clone() else {
+        return;
+    };
+    let board_settings = Settings {
+        pattern: None,
+        replay: None,
+        ..settings.clone()
+    };
+    match load_board(&path, &board_settings, &mut sim_tick) {
+        Ok(loaded) => {
+            settings.width = loaded.board.width();
+            settings.height = loaded.board.height();
+            *game_data = loaded;
+            info!("loaded {}", path.display());
+        }
+        Err(err) => error!("failed to load {}: {err}", path.display()),
+    }
+}
+
+/// Ctrl+S saves the current state, Ctrl+O loads it back.
+pub fn save_load_hotkeys(
+    actions: Actions,
+    mut game_data: ResMut<GameData>,
+    mut sim_tick: ResMut<SimulationTick>,
+) {
+    let path = Path::new(DEFAULT_SAVE_PATH);
+    if actions.just_pressed(Action::Save) {
+        match save_to_file(path, &game_data, &sim_tick) {
+            Ok(()) => info!("saved {DEFAULT_SAVE_PATH}"),
+            Err(err) => error!("failed to save {DEFAULT_SAVE_PATH}: {err}"),
+        }
+    } else if actions.just_pressed(Action::Load) {
+        match load_from_file(path, &mut game_data, &mut sim_tick) {
+            Ok(()) => info!("loaded {DEFAULT_SAVE_PATH}"),
+            Err(err) => error!("failed to load {DEFAULT_SAVE_PATH}: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_round_trip_replaces_the_board() {
+        let settings = Settings {
+            width: 24,
+            height: 18,
+            seed: Some(3),
+            density: 0.4,
+            ..Default::default()
+        };
+        let mut saved = GameData::new(&settings);
+        saved.generation = 42;
+        saved.rule = "B36/S23".parse().unwrap();
+        let mut sim_tick = SimulationTick {
+            timer: Timer::new(Duration::from_millis(120), TimerMode::Repeating),
+        };
+        sim_tick.timer.pause();
+        let path = std::env::temp_dir().join(format!("save-test-{}.json", std::process::id()));
+        save_to_file(&path, &saved, &sim_tick).unwrap();
+
+        let mut loaded_tick = SimulationTick {
+            timer: Timer::new(Duration::from_millis(50), TimerMode::Repeating),
+        };
+        let loaded = load_board(
+            &path,
+            &Settings {
+                width: 10,
+                height: 10,
+                density: 1.0,
+                ..Default::default()
+            },
+            &mut loaded_tick,
+        );
+        let mut full = GameData::new(&Settings {
+            density: 1.0,
+            ..settings.clone()
+        });
+        let reloaded = load_from_file(&path, &mut full, &mut loaded_tick);
+        fs::remove_file(&path).unwrap();
+        reloaded.unwrap();
+        assert!(full.board == saved.board);
+        let loaded = loaded.unwrap();
+        assert!(loaded.board == saved.board);
+        assert_eq!(loaded.generation, 42);
+        assert_eq!(loaded.seed, 3);
+        assert!(loaded.rule == saved.rule);
+        assert_eq!(loaded_tick.timer.duration(), Duration::from_millis(120));
+        assert!(loaded_tick.timer.paused());
+    }
+}
@@ -0,0 +1,170 @@
+//! Rules written in [Rhai](https://rhai.rs). A script defines
+//!
+//! ```text
+//! fn next(alive, neighbor_count, neighbors) {
+//!     neighbor_count == 3 || alive && neighbor_count == 2
+//! }
+//! ```
+//!
+//! where `neighbors` holds the eight surrounding cells, bottom row first,
+//! and the result is a bool or a number that is non-zero for a live cell.
+//! The file is reloaded whenever it changes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use rayon::prelude::*;
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+
+use crate::board::BoundaryMode;
+use crate::config::Automaton;
+use crate::GameData;
+
+/// Seconds between checks for a changed script.
+const RELOAD_INTERVAL: f32 = 1.0;
+
+/// Most operations one call to `next` may run, so that a script stuck in a
+/// loop fails the step instead of hanging the app.
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+
+const NEIGHBORS: [[isize; 2]; 8] = [
+    [-1, -1],
+    [0, -1],
+    [1, -1],
+    [-1, 0],
+    [1, 0],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+];
+
+pub struct RuleScript {
+    engine: Engine,
+    ast: AST,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+impl RuleScript {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS);
+        let modified = modified(path);
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|err| err.to_string())?;
+        Ok(RuleScript {
+            engine,
+            ast,
+            path: path.to_path_buf(),
+            modified,
+        })
+    }
+
+    fn next_state(&self, alive: bool, neighbors: Array) -> Result<bool, String> {
+        let count = neighbors
+            .iter()
+            .filter(|cell| cell.as_bool() == Ok(true))
+            .count();
+        let result: Dynamic = self
+            .engine
+            .call_fn(
+                &mut Scope::new(),
+                &self.ast,
+                "next",
+                (alive, count as i64, neighbors),
+            )
+            .map_err(|err| err.to_string())?;
+        result
+            .as_bool()
+            .or_else(|_| result.as_int().map(|state| state != 0))
+            .map_err(|kind| format!("`next` returned a {kind}, not a bool"))
+    }
+}
+
+pub fn script_mode(game_data: Res<GameData>) -> bool {
+    game_data.automaton == Automaton::Script
+}
+
+/// Runs the script's `next` for every cell. A script error leaves the board
+/// as it was and is logged.
+pub fn step(game_data: &mut GameData, boundary: BoundaryMode) {
+    let Some(script) = &game_data.script else {
+        return;
+    };
+    let board = &game_data.board;
+    let (width, height) = (board.width(), board.height());
+    let rows: Result<Vec<Vec<bool>>, String> = (0..height)
+        .into_par_iter()
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let neighbors = NEIGHBORS
+                        .iter()
+                        .map(|[dx, dy]| {
+                            Dynamic::from(board.get_resolved(
+                                x as isize + dx,
+                                y as isize + dy,
+                                boundary,
+                            ))
+                        })
+                        .collect();
+                    script.next_state(board.get(x, y), neighbors)
+                })
+                .collect()
+        })
+        .collect();
+    match rows {
+        Ok(rows) => {
+            for (y, row) in rows.into_iter().enumerate() {
+                for (x, alive) in row.into_iter().enumerate() {
+                    game_data.board.set(x, y, alive);
+                }
+            }
+        }
+        Err(err) => error!("rule script failed: {err}"),
+    }
+}
+
+/// Recompiles the script when its file changes, keeping the old one if the
+/// new version does not compile.
+pub fn reload_script(
+    time: Res<Time>,
+    mut since_check: Local<f32>,
+    mut game_data: ResMut<GameData>,
+) {
+    *since_check += time.delta_seconds();
+    if *since_check < RELOAD_INTERVAL {
+        return;
+    }
+    *since_check = 0.0;
+    let Some(script) = &game_data.script else {
+        return;
+    };
+    let modified = modified(&script.path);
+    if modified == script.modified {
+        return;
+    }
+    let path = script.path.clone();
+    match RuleScript::load(&path) {
+        Ok(script) => {
+            game_data.script = Some(script);
+            info!("reloaded {}", path.display());
+        }
+        Err(err) => {
+            error!("failed to reload {}: {err}", path.display());
+            if let Some(script) = &mut game_data.script {
+                script.modified = modified;
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use bevy::prelude::*;
+
+use crate::bindings::{Action, Actions, Keybindings};
+use crate::camera::CursorCell;
+use crate::config::Settings;
+use crate::pattern::Pattern;
+use crate::topology::GridTopology;
+use crate::GameData;
+
+#[derive(Resource, Default)]
+pub struct Selection {
+    anchor: Option<[usize; 2]>,
+    corner: Option<[usize; 2]>,
+}
+
+impl Selection {
+    /// Inclusive `(min, max)` cell corners of the selected rectangle.
+    pub fn bounds(&self) -> Option<([usize; 2], [usize; 2])> {
+        let (anchor, corner) = (self.anchor?, self.corner?);
+        Some((
+            [anchor[0].min(corner[0]), anchor[1].min(corner[1])],
+            [anchor[0].max(corner[0]), anchor[1].max(corner[1])],
+        ))
+    }
+
+    fn clear(&mut self) {
+        self.anchor = None;
+        self.corner = None;
+    }
+}
+
+/// The most recently copied or cut region.
+#[derive(Resource, Default)]
+pub struct ClipboardPattern(pub Pattern);
+
+/// A pattern following the cursor until a left click places it.
+#[derive(Resource, Default)]
+pub struct PendingPaste(pub Option<Pattern>);
+
+#[derive(Component)]
+pub struct SelectionOutline;
+
+/// Parent of the translucent squares previewing a pending paste.
+#[derive(Component)]
+pub struct PastePreview;
+
+pub fn setup_selection_outline(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.3, 0.6, 1.0, 0.3),
+                ..Default::default()
+            },
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        SelectionOutline,
+    ));
+}
+
+pub fn select_region(
+    game_data: Res<GameData>,
+    cursor: CursorCell,
+    actions: Actions,
+    mut selection: ResMut<Selection>,
+) {
+    if actions.just_pressed(Action::Cancel) {
+        selection.clear();
+        return;
+    }
+    let Some(cell) = cursor.cell(&game_data.board) else {
+        return;
+    };
+    if actions.just_pressed(Action::Select) {
+        selection.anchor = Some(cell);
+        selection.corner = Some(cell);
+    } else if actions.pressed(Action::Select) {
+        selection.corner = Some(cell);
+    }
+}
+
+pub fn edit_selection(
+    mut game_data: ResMut<GameData>,
+    mut clipboard: ResMut<ClipboardPattern>,
+    selection: Res<Selection>,
+    actions: Actions,
+) {
+    let Some((min, max)) = selection.bounds() else {
+        return;
+    };
+    let cut = actions.just_pressed(Action::Cut);
+    let copy = cut || actions.just_pressed(Action::Copy);
+    let clear = cut || actions.just_pressed(Action::DeleteSelection);
+
+    if copy {
+        clipboard.0 = Pattern::from_region(&game_data.board, min, max);
+        set_system_clipboard_text(clipboard.0.to_rle());
+    }
+    if clear {
+        for y in min[1]..=max[1] {
+            for x in min[0]..=max[0] {
+                game_data.board.set(x, y, false);
+            }
+        }
+    }
+}
+
+/// What [`transform_selection`] does to the selected cells.
+enum SelectionTransform {
+    Rotate,
+    FlipHorizontally,
+    FlipVertically,
+    Nudge([isize; 2]),
+}
+
+/// With a region selected, `Q` turns it a quarter turn clockwise about its
+/// top-left corner, `X` and `Y` flip it left to right and top to bottom, and
+/// the arrow keys nudge it a cell. Nudges are used up so the arrows don't
+/// also scrub through history, and stop at the edge of the board.
+pub fn transform_selection(
+    mut game_data: ResMut<GameData>,
+    mut selection: ResMut<Selection>,
+    bindings: Res<Keybindings>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+) {
+    let Some((min, max)) = selection.bounds() else {
+        return;
+    };
+    let nudges = [
+        (Action::NudgeLeft, [-1, 0]),
+        (Action::NudgeRight, [1, 0]),
+        (Action::NudgeUp, [0, 1]),
+        (Action::NudgeDown, [0, -1]),
+    ];
+    let pressed = |action| bindings.just_pressed(action, &keyboard, &mouse);
+    let transform = if pressed(Action::RotateSelection) {
+        SelectionTransform::Rotate
+    } else if pressed(Action::FlipHorizontally) {
+        SelectionTransform::FlipHorizontally
+    } else if pressed(Action::FlipVertically) {
+        SelectionTransform::FlipVertically
+    } else if let Some(&(action, step)) = nudges.iter().find(|(action, _)| pressed(*action)) {
+        bindings.release(action, &mut keyboard, &mut mouse);
+        SelectionTransform::Nudge(step)
+    } else {
+        return;
+    };
+
+    let board = &mut game_data.board;
+    let original = Pattern::from_region(board, min, max);
+    let top_left = [min[0], max[1]];
+    let (pattern, top_left) = match transform {
+        SelectionTransform::Rotate => (original.rotated_clockwise(), top_left),
+        SelectionTransform::FlipHorizontally => (original.flipped_horizontally(), top_left),
+        SelectionTransform::FlipVertically => (original.flipped_vertically(), top_left),
+        SelectionTransform::Nudge([dx, dy]) => {
+            let moved_min = board.checked(min[0] as isize + dx, min[1] as isize + dy);
+            let moved_max = board.checked(max[0] as isize + dx, max[1] as isize + dy);
+            let (Some(_), Some(_)) = (moved_min, moved_max) else {
+                return;
+            };
+            let x = top_left[0] as isize + dx;
+            let y = top_left[1] as isize + dy;
+            (original, [x as usize, y as usize])
+        }
+    };
+
+    for y in min[1]..=max[1] {
+        for x in min[0]..=max[0] {
+            board.set(x, y, false);
+        }
+    }
+    pattern.stamp(board, top_left);
+    // The new bounds, clipped to the board like the stamp was.
+    let right = (top_left[0] + pattern.width - 1).min(board.width() - 1);
+    let bottom = top_left[1].saturating_sub(pattern.height - 1);
+    selection.anchor = Some([top_left[0], bottom]);
+    selection.corner = Some([right, top_left[1]]);
+}
+
+pub fn render_selection(
+    selection: Res<Selection>,
+    settings: Res<Settings>,
+    mut outlines: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<SelectionOutline>>,
+) {
+    let (mut sprite, mut transform, mut visibility) = outlines.single_mut();
+    let Some((min, max)) = selection.bounds() else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let cell_size = settings.cell_size;
+    let width = (max[0] - min[0] + 1) as f32 * cell_size;
+    let height = (max[1] - min[1] + 1) as f32 * cell_size;
+    sprite.custom_size = Some(Vec2::from([width, height]));
+    transform.translation = Vec3::from([
+        min[0] as f32 * cell_size + width / 2.0,
+        min[1] as f32 * cell_size + height / 2.0,
+        1.0,
+    ]);
+    *visibility = Visibility::Visible;
+}
+
+/// The system clipboard's text, if it holds any.
+#[cfg(not(target_arch = "wasm32"))]
+fn system_clipboard_text() -> Option<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_clipboard_text() -> Option<String> {
+    None
+}
+
+/// Puts `text` on the system clipboard, so a copied region can be pasted
+/// elsewhere and Ctrl+V here picks up the latest copy either way.
+#[cfg(not(target_arch = "wasm32"))]
+fn set_system_clipboard_text(text: String) {
+    if let Err(err) = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+        warn!("failed to copy to the system clipboard: {err}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn set_system_clipboard_text(_text: String) {}
+
+/// Ctrl+V picks up a pattern to place: RLE or plaintext from the system
+/// clipboard if it holds one, otherwise the last copied region.
+pub fn begin_paste(
+    actions: Actions,
+    clipboard: Res<ClipboardPattern>,
+    mut pending: ResMut<PendingPaste>,
+) {
+    if actions.just_pressed(Action::Paste) {
+        let pasted = system_clipboard_text().and_then(|text| Pattern::parse(&text).ok());
+        let pattern = pasted.unwrap_or_else(|| clipboard.0.clone());
+        if pattern.width > 0 && pattern.height > 0 {
+            pending.0 = Some(pattern);
+        }
+    } else if actions.just_pressed(Action::Cancel) {
+        pending.0 = None;
+    }
+}
+
+/// Drawing stamps the pending paste with its top-left corner under the
+/// cursor. The press is used up so it doesn't also paint.
+pub fn place_paste(
+    mut game_data: ResMut<GameData>,
+    mut pending: ResMut<PendingPaste>,
+    bindings: Res<Keybindings>,
+    mut keyboard: ResMut<Input<KeyCode>>,
+    mut mouse: ResMut<Input<MouseButton>>,
+    cursor: CursorCell,
+) {
+    if !bindings.just_pressed(Action::Draw, &keyboard, &mouse) {
+        return;
+    }
+    let Some(cell) = cursor.cell(&game_data.board) else {
+        return;
+    };
+    if let Some(pattern) = pending.0.take() {
+        pattern.stamp(&mut game_data.board, cell);
+        bindings.release(Action::Draw, &mut keyboard, &mut mouse);
+    }
+}
+
+pub fn setup_paste_preview(mut commands: Commands) {
+    commands.spawn((SpatialBundle::default(), PastePreview));
+}
+
+/// Shows the pending paste as a ghost under the cursor.
+pub fn update_paste_preview(
+    mut commands: Commands,
+    pending: Res<PendingPaste>,
+    settings: Res<Settings>,
+    game_data: Res<GameData>,
+    topology: Res<GridTopology>,
+    cursor: CursorCell,
+    mut previews: Query<(Entity, &mut Transform, &mut Visibility), With<PastePreview>>,
+) {
+    let Ok((preview, mut transform, mut visibility)) = previews.get_single_mut() else {
+        return;
+    };
+    let cell_size = settings.cell_size;
+    if pending.is_changed() {
+        commands.entity(preview).despawn_descendants();
+        if let Some(pattern) = &pending.0 {
+            commands.entity(preview).with_children(|parent| {
+                for row in 0..pattern.height {
+                    for col in (0..pattern.width).filter(|&col| pattern.get(col, row)) {
+                        parent.spawn(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::rgba(0.3, 1.0, 0.5, 0.4),
+                                custom_size: Some(Vec2::splat(cell_size)),
+                                ..Default::default()
+                            },
+                            transform: Transform::from_xyz(
+                                col as f32 * cell_size,
+                                -(row as f32) * cell_size,
+                                0.0,
+                            ),
+                            ..Default::default()
+                        });
+                    }
+                }
+            });
+        }
+    }
+    match (&pending.0, cursor.cell(&game_data.board)) {
+        (Some(_), Some([x, y])) => {
+            transform.translation = topology.cell_center(x, y, cell_size).extend(3.0);
+            *visibility = Visibility::Visible;
+        }
+        _ => *visibility = Visibility::Hidden,
+    }
+}
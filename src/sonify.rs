@@ -0,0 +1,230 @@
+//! Playing the simulation as music. Each generation, rows where cells were
+//! born sound notes of a scale (higher rows, higher notes) and deaths add a
+//! burst of noise, both louder the more cells took part. `N` turns it on
+//! and off.
+
+use std::cmp::Reverse;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use bevy::audio::{AddAudioSource, Source};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::config::Settings;
+use crate::GameData;
+
+const SAMPLE_RATE: u32 = 44_100;
+const NOTE_LENGTH: Duration = Duration::from_millis(150);
+const BASE_FREQUENCY: f32 = 220.0;
+const OCTAVES: usize = 2;
+/// Most notes sounded for one generation.
+const MAX_NOTES: usize = 4;
+
+/// Which notes births are mapped to.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum MusicalScale {
+    Major,
+    Minor,
+    #[default]
+    Pentatonic,
+    Chromatic,
+}
+
+impl MusicalScale {
+    /// Semitones above the root of each note in one octave.
+    fn steps(self) -> &'static [u8] {
+        match self {
+            MusicalScale::Major => &[0, 2, 4, 5, 7, 9, 11],
+            MusicalScale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            MusicalScale::Pentatonic => &[0, 2, 4, 7, 9],
+            MusicalScale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        }
+    }
+
+    /// Frequencies of the scale over [`OCTAVES`] octaves, lowest first.
+    fn frequencies(self) -> Vec<f32> {
+        (0..OCTAVES)
+            .flat_map(|octave| {
+                self.steps()
+                    .iter()
+                    .map(move |&step| (octave * 12 + step as usize) as f32)
+            })
+            .map(|semitones| BASE_FREQUENCY * 2f32.powf(semitones / 12.0))
+            .collect()
+    }
+}
+
+/// A short plucked note, or a noise burst when `frequency` is `None`.
+#[derive(TypeUuid)]
+#[uuid = "6b1f3c8e-3c1a-4f0e-9a57-5d1f4c0be2a1"]
+pub struct Tone {
+    frequency: Option<f32>,
+}
+
+pub struct ToneDecoder {
+    frequency: Option<f32>,
+    sample: u32,
+    noise: u32,
+}
+
+impl Iterator for ToneDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let length = (NOTE_LENGTH.as_secs_f32() * SAMPLE_RATE as f32) as u32;
+        if self.sample >= length {
+            return None;
+        }
+        let t = self.sample as f32 / SAMPLE_RATE as f32;
+        let envelope = 1.0 - self.sample as f32 / length as f32;
+        self.sample += 1;
+        let wave = match self.frequency {
+            Some(frequency) => (t * frequency * TAU).sin(),
+            None => {
+                // xorshift, which is plenty for percussion.
+                self.noise ^= self.noise << 13;
+                self.noise ^= self.noise >> 17;
+                self.noise ^= self.noise << 5;
+                self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+            }
+        };
+        Some(wave * envelope * envelope)
+    }
+}
+
+impl Source for ToneDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(NOTE_LENGTH)
+    }
+}
+
+impl bevy::audio::Decodable for Tone {
+    type DecoderItem = f32;
+    type Decoder = ToneDecoder;
+
+    fn decoder(&self) -> ToneDecoder {
+        ToneDecoder {
+            frequency: self.frequency,
+            sample: 0,
+            noise: 0x9e37_79b9,
+        }
+    }
+}
+
+pub struct SonifyPlugin;
+
+impl Plugin for SonifyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Tone>()
+            .add_system(toggle_audio)
+            .add_system(play_generation);
+    }
+}
+
+pub fn toggle_audio(actions: Actions, mut settings: ResMut<Settings>) {
+    if actions.just_pressed(Action::Audio) {
+        settings.audio = !settings.audio;
+        info!("audio: {}", if settings.audio { "on" } else { "off" });
+    }
+}
+
+/// Notes for the current scale plus the noise burst, rebuilt when the
+/// scale changes.
+#[derive(Default)]
+pub struct ToneBank {
+    scale: Option<MusicalScale>,
+    notes: Vec<Handle<Tone>>,
+    noise: Handle<Tone>,
+}
+
+/// Sounds the births and deaths since the last generation heard.
+pub fn play_generation(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    audio: Res<Audio<Tone>>,
+    mut tones: ResMut<Assets<Tone>>,
+    mut bank: Local<ToneBank>,
+    mut previous: Local<Option<(u64, Board)>>,
+) {
+    let generation = game_data.generation;
+    let heard = previous
+        .as_ref()
+        .is_some_and(|(last, _)| *last == generation);
+    if heard {
+        return;
+    }
+    let Some((_, before)) = previous.replace((generation, game_data.board.clone())) else {
+        return;
+    };
+    let board = &game_data.board;
+    if !settings.audio || before.width() != board.width() || before.height() != board.height() {
+        return;
+    }
+
+    if bank.scale != Some(settings.audio_scale) {
+        bank.scale = Some(settings.audio_scale);
+        bank.notes = settings
+            .audio_scale
+            .frequencies()
+            .into_iter()
+            .map(|frequency| {
+                tones.add(Tone {
+                    frequency: Some(frequency),
+                })
+            })
+            .collect();
+        bank.noise = tones.add(Tone { frequency: None });
+    }
+
+    // Births per band of rows, one band per note, bottom to top.
+    let mut births = vec![0usize; bank.notes.len()];
+    let mut deaths = 0;
+    for y in 0..board.height() {
+        let band = y * births.len() / board.height();
+        for x in 0..board.width() {
+            match (before.get(x, y), board.get(x, y)) {
+                (false, true) => births[band] += 1,
+                (true, false) => deaths += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let cells = (board.width() * board.height()).max(1) as f32;
+    let volume = |count: usize| {
+        // Loud enough to hear a single glider on a large board.
+        settings.audio_volume * (count as f32 / cells * 200.0).sqrt().min(1.0)
+    };
+    let mut bands: Vec<(usize, usize)> = births.into_iter().enumerate().collect();
+    bands.sort_by_key(|&(_, count)| Reverse(count));
+    for &(band, count) in bands.iter().take(MAX_NOTES).filter(|(_, count)| *count > 0) {
+        audio.play_with_settings(
+            bank.notes[band].clone(),
+            PlaybackSettings::ONCE.with_volume(volume(count)),
+        );
+    }
+    if deaths > 0 {
+        audio.play_with_settings(
+            bank.noise.clone(),
+            PlaybackSettings::ONCE.with_volume(volume(deaths) * 0.3),
+        );
+    }
+}
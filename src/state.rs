@@ -0,0 +1,314 @@
+//! The app's modes: a main menu for setting up a board, an editor where the
+//! simulation is stopped and the drawing tools work, and running.
+//!
+//! The tick timer stays the switch between editing and running, since
+//! `Space`, history scrubbing, auto-pause and the gamepad all pause and
+//! unpause it directly: [`follow_pause`] moves to [`AppState::Edit`] or
+//! [`AppState::Run`] to match. `F1` goes back to the menu.
+
+use std::path::Path;
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+
+use crate::bindings::{Action, Actions};
+use crate::board::Board;
+use crate::brush::BrushPreview;
+use crate::config::{Backend, Settings};
+use crate::panel::BoardResized;
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+use crate::selection::PastePreview;
+use crate::{save, GameData, SimulationTick};
+
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AppState {
+    #[default]
+    Menu,
+    /// Stopped, with every drawing tool active.
+    Edit,
+    Run,
+}
+
+/// Systems that only run once a board is set up, in either
+/// [`AppState::Edit`] or [`AppState::Run`].
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct InGame;
+
+/// The board being set up in the menu.
+struct MenuForm {
+    size: [usize; 2],
+    rule: String,
+    density: f64,
+    /// A soup seed, or blank for a random one.
+    seed: String,
+    /// A pattern or saved state to start from, or blank for none.
+    file: String,
+}
+
+#[derive(Resource, Default)]
+pub struct MainMenu {
+    form: Option<MenuForm>,
+    error: Option<String>,
+    /// The state the menu was opened from, to go back to without changes.
+    previous: Option<AppState>,
+}
+
+pub struct AppStatePlugin;
+
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<AppState>()
+            .init_resource::<MainMenu>()
+            .configure_set(InGame.run_if(not(in_state(AppState::Menu))))
+            .add_startup_system(skip_main_menu.after(save::load_from_args))
+            .add_system(pause.in_schedule(OnEnter(AppState::Menu)))
+            .add_system(pause.in_schedule(OnEnter(AppState::Edit)))
+            .add_system(hide_tool_previews.in_schedule(OnExit(AppState::Edit)))
+            .add_system(unpause.in_schedule(OnEnter(AppState::Run)))
+            .add_system(
+                main_menu
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem)
+                    .after(EguiSet::BeginFrame)
+                    .run_if(in_state(AppState::Menu)),
+            )
+            .add_system(open_main_menu.in_set(InGame))
+            .add_system(
+                follow_pause
+                    .in_base_set(CoreSet::PostUpdate)
+                    .run_if(not(in_state(AppState::Menu))),
+            );
+    }
+}
+
+/// Goes straight to the board when the menu is turned off or the command
+/// line already says what to start from.
+fn skip_main_menu(
+    settings: Res<Settings>,
+    sim_tick: Res<SimulationTick>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let given = settings.pattern.is_some()
+        || settings.load.is_some()
+        || settings.replay.is_some()
+        || settings.host.is_some()
+        || settings.join.is_some();
+    if !settings.main_menu || given {
+        next_state.set(if sim_tick.timer.paused() {
+            AppState::Edit
+        } else {
+            AppState::Run
+        });
+    }
+}
+
+fn pause(mut sim_tick: ResMut<SimulationTick>) {
+    sim_tick.timer.pause();
+}
+
+fn unpause(mut sim_tick: ResMut<SimulationTick>) {
+    sim_tick.timer.unpause();
+}
+
+type ToolPreviews = Or<(With<BrushPreview>, With<PastePreview>)>;
+
+/// The brush and paste previews stop following the cursor outside the
+/// editor, so they are hidden rather than left where they were.
+fn hide_tool_previews(mut previews: Query<&mut Visibility, ToolPreviews>) {
+    for mut visibility in &mut previews {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Moves between editing and running whenever the timer was paused or
+/// unpaused, unless another state was already asked for this frame.
+fn follow_pause(
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    sim_tick: Res<SimulationTick>,
+) {
+    if next_state.0.is_some() {
+        return;
+    }
+    match (state.0, sim_tick.timer.paused()) {
+        (AppState::Run, true) => next_state.set(AppState::Edit),
+        (AppState::Edit, false) => next_state.set(AppState::Run),
+        _ => {}
+    }
+}
+
+/// `F1` opens the menu over the current board.
+fn open_main_menu(
+    actions: Actions,
+    state: Res<State<AppState>>,
+    mut menu: ResMut<MainMenu>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if actions.just_pressed(Action::MainMenu) {
+        menu.previous = Some(state.0);
+        next_state.set(AppState::Menu);
+    }
+}
+
+/// Builds the board the form describes and the settings it was built
+/// with, or says what is wrong with them.
+fn new_board(
+    form: &MenuForm,
+    settings: &Settings,
+    sim_tick: &mut SimulationTick,
+) -> Result<(Settings, GameData), String> {
+    let rule = form.rule.parse::<Rule>()?;
+    if settings.backend == Backend::Gpu && !(rule.is_classic() && rule.states <= 2) {
+        return Err(String::from(
+            "the GPU backend only runs two-state Moore rules",
+        ));
+    }
+    let seed = match form.seed.trim() {
+        "" => None,
+        seed => Some(
+            seed.parse::<u64>()
+                .map_err(|_| format!("`{seed}` is not a seed"))?,
+        ),
+    };
+    let mut new_settings = Settings {
+        width: form.size[0],
+        height: form.size[1],
+        rule,
+        density: form.density,
+        ..settings.clone()
+    };
+    // The form starts out showing any `--seed`, so what it says now wins.
+    let board_settings = Settings {
+        seed,
+        cli_seed: None,
+        pattern: None,
+        load: None,
+        replay: None,
+        ..new_settings.clone()
+    };
+
+    let file = Path::new(form.file.trim());
+    let game_data = if form.file.trim().is_empty() {
+        GameData::new(&board_settings)
+    } else if file
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        let game_data = save::load_board(file, &board_settings, sim_tick)
+            .map_err(|err| format!("failed to load {}: {err}", file.display()))?;
+        new_settings.width = game_data.board.width();
+        new_settings.height = game_data.board.height();
+        new_settings.rule = game_data.rule;
+        game_data
+    } else {
+        let pattern = Pattern::load(file)
+            .map_err(|err| format!("failed to load {}: {err}", file.display()))?;
+        let mut game_data = GameData::new(&board_settings);
+        pattern.stamp_centered(&mut game_data.board);
+        game_data
+    };
+    // The GPU board images are only created at startup.
+    let size = [settings.width, settings.height];
+    if settings.backend == Backend::Gpu && [new_settings.width, new_settings.height] != size {
+        return Err(format!(
+            "the GPU backend can't change the board from {}x{}",
+            size[0], size[1]
+        ));
+    }
+    Ok((new_settings, game_data))
+}
+
+/// Draws the menu. `Edit` and `Run` replace the board with the one set up
+/// in the form; `Back` returns to the board the menu was opened over.
+fn main_menu(
+    mut contexts: EguiContexts,
+    mut menu: ResMut<MainMenu>,
+    mut settings: ResMut<Settings>,
+    mut game_data: ResMut<GameData>,
+    mut sim_tick: ResMut<SimulationTick>,
+    mut resized: EventWriter<BoardResized>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let menu = &mut *menu;
+    let form = menu.form.get_or_insert_with(|| MenuForm {
+        size: [settings.width, settings.height],
+        rule: settings.rule.to_string(),
+        density: settings.density,
+        seed: settings
+            .seed()
+            .map(|seed| seed.to_string())
+            .unwrap_or_default(),
+        file: String::new(),
+    });
+    let mut start = None;
+    egui::Window::new("Cellular Automata")
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .collapsible(false)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let [width, height] = &mut form.size;
+            ui.horizontal(|ui| {
+                let resizable = settings.backend == Backend::Cpu;
+                ui.label("size");
+                ui.add_enabled(resizable, egui::DragValue::new(width).clamp_range(8..=4096));
+                ui.label("×");
+                ui.add_enabled(
+                    resizable,
+                    egui::DragValue::new(height).clamp_range(8..=4096),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("rule");
+                ui.text_edit_singleline(&mut form.rule);
+            });
+            ui.add(egui::Slider::new(&mut form.density, 0.0..=1.0).text("fill density"));
+            ui.horizontal(|ui| {
+                ui.label("seed");
+                ui.add(egui::TextEdit::singleline(&mut form.seed).hint_text("random"));
+            });
+            ui.horizontal(|ui| {
+                ui.label("file");
+                ui.add(egui::TextEdit::singleline(&mut form.file).hint_text("pattern or save"));
+            });
+            if let Some(err) = &menu.error {
+                ui.colored_label(egui::Color32::LIGHT_RED, err);
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Edit").clicked() {
+                    start = Some(AppState::Edit);
+                }
+                if ui.button("Run").clicked() {
+                    start = Some(AppState::Run);
+                }
+                if let Some(previous) = menu.previous {
+                    if ui.button("Back").clicked() {
+                        next_state.set(previous);
+                    }
+                }
+            });
+        });
+
+    let Some(state) = start else {
+        return;
+    };
+    match new_board(form, &settings, &mut sim_tick) {
+        Ok((new_settings, board)) => {
+            *settings = new_settings;
+            let size = |board: &Board| (board.width(), board.height());
+            if size(&board.board) != size(&game_data.board) {
+                resized.send(BoardResized);
+            }
+            *game_data = board;
+            if settings.density > 0.0 {
+                info!("random soup seed: {}", game_data.seed);
+            }
+            menu.error = None;
+            next_state.set(state);
+        }
+        Err(err) => menu.error = Some(err),
+    }
+}
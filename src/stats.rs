@@ -0,0 +1,91 @@
+//! Logging statistics for every generation to a CSV file with
+//! `--stats <path>`, for plotting long runs afterwards.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use crate::board::{Board, BoundaryMode};
+use crate::config::Settings;
+use crate::topology::GridTopology;
+use crate::GameData;
+
+const HEADER: &str = "generation,population,births,deaths,step_ms";
+
+/// The CSV file rows are written to, when logging.
+#[derive(Resource, Default)]
+pub struct StatsLog(Option<BufWriter<File>>);
+
+impl StatsLog {
+    /// Starts the file named by `settings.stats`, if any.
+    pub fn new(settings: &Settings) -> Self {
+        let Some(path) = &settings.stats else {
+            return StatsLog(None);
+        };
+        match create(path) {
+            Ok(file) => StatsLog(Some(file)),
+            Err(err) => {
+                eprintln!("failed to create {}: {err}", path.display());
+                StatsLog(None)
+            }
+        }
+    }
+
+    /// Steps `game_data` once, timing the step and logging the result when
+    /// a file is open.
+    pub fn step(
+        &mut self,
+        game_data: &mut GameData,
+        next_board: &mut Board,
+        boundary: BoundaryMode,
+        topology: GridTopology,
+    ) {
+        if self.0.is_none() {
+            game_data.step(next_board, boundary, topology);
+            return;
+        }
+        let previous = game_data.board.clone();
+        let start = Instant::now();
+        game_data.step(next_board, boundary, topology);
+        self.record(game_data, &previous, start.elapsed());
+    }
+
+    /// Writes a row for the generation just stepped from `previous`. Logging
+    /// stops at the first write error rather than reporting it every step.
+    fn record(&mut self, game_data: &GameData, previous: &Board, duration: Duration) {
+        let Some(file) = &mut self.0 else {
+            return;
+        };
+        let board = &game_data.board;
+        let (births, deaths) = board.changes_since(previous);
+        let row = writeln!(
+            file,
+            "{},{},{births},{deaths},{:.3}",
+            game_data.generation,
+            board.population(),
+            duration.as_secs_f64() * 1000.0
+        );
+        if let Err(err) = row {
+            error!("failed to write stats: {err}");
+            self.0 = None;
+        }
+    }
+
+    /// Pushes buffered rows to the file, since the app may exit without
+    /// dropping it.
+    pub fn flush(&mut self) {
+        if let Some(Err(err)) = self.0.as_mut().map(|file| file.flush()) {
+            error!("failed to write stats: {err}");
+            self.0 = None;
+        }
+    }
+}
+
+fn create(path: &Path) -> io::Result<BufWriter<File>> {
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(file, "{HEADER}")?;
+    Ok(file)
+}
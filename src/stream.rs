@@ -0,0 +1,222 @@
+//! Serving the live board to external tools over TCP.
+//!
+//! `--serve <ADDR>` accepts any number of connections and writes one frame
+//! to each of them per generation:
+//!
+//! - `json`: one line per frame, `{"generation":..,"width":..,"height":..,
+//!   "alive":[[x,y],..]}`.
+//! - `binary`: `width` and `height` as little-endian `u32`, `generation` as
+//!   a little-endian `u64`, then each row from `y = 0` up, packed into
+//!   `width.div_ceil(8)` bytes with cell `x` at bit `x % 8` of byte `x / 8`.
+//!
+//! Either way, each line a client sends is a JSON command:
+//! `{"type":"set-cell","x":..,"y":..,"alive":..}` or `{"type":"step"}`.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use bevy::prelude::*;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::config::Settings;
+use crate::{GameData, SetCellEvent, StepEvent};
+
+/// Unsent bytes a client may fall behind by before it is dropped.
+const MAX_BACKLOG: usize = 16 << 20;
+/// Received bytes a client may have waiting in one frame before it is
+/// dropped, so one that never ends a line can't use up memory.
+const MAX_INCOMING: usize = 1 << 20;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum StreamFormat {
+    #[default]
+    Json,
+    Binary,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum Command {
+    SetCell { x: usize, y: usize, alive: bool },
+    Step,
+}
+
+#[derive(Serialize)]
+struct JsonFrame {
+    generation: u64,
+    width: usize,
+    height: usize,
+    alive: Vec<[usize; 2]>,
+}
+
+struct Client {
+    stream: TcpStream,
+    /// Bytes of a command line received so far.
+    incoming: Vec<u8>,
+    /// Frames not yet taken by the socket.
+    outgoing: Vec<u8>,
+}
+
+impl Client {
+    /// Writes as much of the backlog as the socket takes without blocking.
+    fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+        if self.outgoing.len() > MAX_BACKLOG {
+            return Err(io::Error::other("too far behind"));
+        }
+        Ok(())
+    }
+
+    /// Every complete command line received since the last call.
+    fn receive(&mut self) -> io::Result<Vec<String>> {
+        let mut buffer = [0; 4096];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => self.incoming.extend_from_slice(&buffer[..read]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+            if self.incoming.len() > MAX_INCOMING {
+                return Err(io::Error::other("sent too much at once"));
+            }
+        }
+        let mut lines = Vec::new();
+        while let Some(end) = self.incoming.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.incoming.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim().to_string());
+        }
+        Ok(lines)
+    }
+}
+
+#[derive(Resource)]
+pub struct BoardServer {
+    listener: TcpListener,
+    format: StreamFormat,
+    clients: Vec<Client>,
+    /// The generation last sent, so each one goes out once.
+    sent: Option<u64>,
+}
+
+/// Starts listening when `--serve` is given.
+pub fn start_server(mut commands: Commands, settings: Res<Settings>) {
+    let Some(addr) = &settings.serve else {
+        return;
+    };
+    let listener = TcpListener::bind(addr)
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener));
+    match listener {
+        Ok(listener) => {
+            info!("serving the board on {addr}");
+            commands.insert_resource(BoardServer {
+                listener,
+                format: settings.serve_format,
+                clients: Vec::new(),
+                sent: None,
+            });
+        }
+        Err(err) => error!("failed to serve on {addr}: {err}"),
+    }
+}
+
+fn encode(game_data: &GameData, format: StreamFormat) -> Vec<u8> {
+    let board = &game_data.board;
+    match format {
+        StreamFormat::Json => {
+            let frame = JsonFrame {
+                generation: game_data.generation,
+                width: board.width(),
+                height: board.height(),
+                alive: alive_cells(board),
+            };
+            let mut bytes = serde_json::to_vec(&frame).expect("frames always serialize");
+            bytes.push(b'\n');
+            bytes
+        }
+        StreamFormat::Binary => {
+            let row_bytes = board.width().div_ceil(8);
+            let mut bytes = Vec::with_capacity(16 + row_bytes * board.height());
+            bytes.extend_from_slice(&(board.width() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(board.height() as u32).to_le_bytes());
+            bytes.extend_from_slice(&game_data.generation.to_le_bytes());
+            for y in 0..board.height() {
+                let start = bytes.len();
+                bytes.resize(start + row_bytes, 0);
+                for x in (0..board.width()).filter(|&x| board.get(x, y)) {
+                    bytes[start + x / 8] |= 1 << (x % 8);
+                }
+            }
+            bytes
+        }
+    }
+}
+
+fn alive_cells(board: &Board) -> Vec<[usize; 2]> {
+    let mut alive = Vec::new();
+    for y in 0..board.height() {
+        for x in (0..board.width()).filter(|&x| board.get(x, y)) {
+            alive.push([x, y]);
+        }
+    }
+    alive
+}
+
+/// Takes in new connections and their commands, and queues a frame for
+/// everyone whenever a new generation has been stepped.
+pub fn serve_board(
+    mut server: ResMut<BoardServer>,
+    game_data: Res<GameData>,
+    mut set_cells: EventWriter<SetCellEvent>,
+    mut steps: EventWriter<StepEvent>,
+) {
+    let server = &mut *server;
+    while let Ok((stream, addr)) = server.listener.accept() {
+        if let Err(err) = stream.set_nonblocking(true) {
+            warn!("failed to accept {addr}: {err}");
+            continue;
+        }
+        info!("{addr} connected to the board stream");
+        server.clients.push(Client {
+            stream,
+            incoming: Vec::new(),
+            outgoing: encode(&game_data, server.format),
+        });
+    }
+
+    let frame = (server.sent != Some(game_data.generation)).then(|| {
+        server.sent = Some(game_data.generation);
+        encode(&game_data, server.format)
+    });
+    server.clients.retain_mut(|client| {
+        let lines = match client.receive() {
+            Ok(lines) => lines,
+            Err(_) => return false,
+        };
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(Command::SetCell { x, y, alive }) => {
+                    set_cells.send(SetCellEvent { x, y, alive })
+                }
+                Ok(Command::Step) => steps.send(StepEvent),
+                Err(err) => warn!("ignoring a bad command: {err}"),
+            }
+        }
+        if let Some(frame) = &frame {
+            client.outgoing.extend_from_slice(frame);
+        }
+        client.flush().is_ok()
+    });
+}
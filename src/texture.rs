@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::config::{Automaton, Backend, RenderMode, Settings};
+use crate::topology::GridTopology;
+use crate::{age, BoardEntity, GameData};
+
+/// The image the whole board is drawn into when using [`RenderMode::Texture`].
+#[derive(Resource)]
+pub struct BoardTexture(Handle<Image>);
+
+pub fn texture_mode(settings: Res<Settings>, topology: Res<GridTopology>) -> bool {
+    settings.backend == Backend::Cpu
+        && settings.render_mode == RenderMode::Texture
+        && !topology.is_hex()
+}
+
+/// The 8-bit sRGB channels of `color`, as stored in an `Rgba8UnormSrgb` image.
+pub fn rgba_u8(color: Color) -> [u8; 4] {
+    color
+        .as_rgba_f32()
+        .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+pub fn spawn_board_texture(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    let handle = images.add(image);
+
+    let board_size = Vec2::new(width as f32, height as f32) * settings.cell_size;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(board_size),
+                ..Default::default()
+            },
+            texture: handle.clone(),
+            transform: Transform::from_translation((board_size / 2.0).extend(0.0)),
+            ..Default::default()
+        },
+        BoardEntity,
+    ));
+    commands.insert_resource(BoardTexture(handle));
+}
+
+/// Writes one pixel per cell whenever the board changes. Image rows run top
+/// to bottom while board rows run bottom to top, hence the flip.
+pub fn update_board_texture(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    texture: Res<BoardTexture>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !game_data.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let Some(image) = images.get_mut(&texture.0) else {
+        return;
+    };
+    let board = &game_data.board;
+    let alive = rgba_u8(settings.cell_color());
+    let plain = !settings.color_by_age && game_data.automaton == Automaton::Life;
+    for y in 0..board.height() {
+        let row = board.height() - 1 - y;
+        for x in 0..board.width() {
+            let offset = (row * board.width() + x) * 4;
+            let pixel = if plain && board.get(x, y) {
+                alive
+            } else {
+                age::cell_color(&game_data, &settings, x, y).map_or([0; 4], rgba_u8)
+            };
+            image.data[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Color themes: a few built-in palettes plus any `[[themes]]` defined in the
+//! config file. `T` cycles through them.
+//!
+//! Choosing a theme copies its colors into [`Settings::background_color`]
+//! and [`Settings::cell_color`], which everything is drawn from.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bindings::{Action, Actions};
+use crate::config::Settings;
+
+/// A named pair of hex colors.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub background: String,
+    pub cells: String,
+}
+
+const BUILT_IN: [(&str, &str, &str); 3] = [
+    ("classic", "191970", "ffffff"),
+    ("solarized", "002b36", "b58900"),
+    ("high-contrast", "000000", "ffff00"),
+];
+
+/// The built-in themes followed by the user's own, which replace built-ins
+/// of the same name.
+pub fn all_themes(settings: &Settings) -> Vec<Theme> {
+    let mut themes: Vec<Theme> = BUILT_IN
+        .iter()
+        .filter(|(name, _, _)| !settings.themes.iter().any(|theme| theme.name == *name))
+        .map(|&(name, background, cells)| Theme {
+            name: String::from(name),
+            background: String::from(background),
+            cells: String::from(cells),
+        })
+        .collect();
+    themes.extend(settings.themes.iter().cloned());
+    themes
+}
+
+/// Switches to the theme called `name`, returning whether there is one.
+pub fn apply_theme(settings: &mut Settings, name: &str) -> bool {
+    let Some(theme) = all_themes(settings)
+        .into_iter()
+        .find(|theme| theme.name == name)
+    else {
+        return false;
+    };
+    settings.background_color = theme.background;
+    settings.cell_color = theme.cells;
+    settings.theme = Some(theme.name);
+    true
+}
+
+/// `T` moves on to the next theme.
+pub fn cycle_theme(
+    actions: Actions,
+    mut settings: ResMut<Settings>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    if !actions.just_pressed(Action::CycleTheme) {
+        return;
+    }
+    let themes = all_themes(&settings);
+    let current = settings
+        .theme
+        .as_ref()
+        .and_then(|name| themes.iter().position(|theme| &theme.name == name));
+    let next = &themes[current.map_or(0, |i| (i + 1) % themes.len())];
+    apply_theme(&mut settings, &next.name);
+    clear_color.0 = settings.background_color();
+    info!("theme: {}", next.name);
+}
@@ -0,0 +1,187 @@
+//! Square and hexagonal lattices.
+//!
+//! Hex boards reuse [`Board`] with axial coordinates: cell `[q, r]` is stored
+//! at `x = q`, `y = r`, so the board is drawn as a parallelogram and wraps
+//! around as a hexagonal torus.
+
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::config::{Backend, Settings};
+use crate::{age, BoardEntity, GameData};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Axial offsets of the six neighbors of a hex cell.
+pub const HEX_NEIGHBORS: [[isize; 2]; 6] = [[1, 0], [-1, 0], [0, 1], [0, -1], [1, -1], [-1, 1]];
+
+#[derive(
+    Resource, Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum GridTopology {
+    #[default]
+    Square,
+    /// Hexagons with a vertex at the top; rows of the board run horizontally.
+    HexPointy,
+    /// Hexagons with a flat top; columns of the board run vertically.
+    HexFlat,
+}
+
+impl GridTopology {
+    pub fn is_hex(self) -> bool {
+        self != GridTopology::Square
+    }
+
+    /// Circumradius of a hexagon whose flat-to-flat width is `cell_size`.
+    fn hex_radius(cell_size: f32) -> f32 {
+        cell_size / SQRT_3
+    }
+
+    /// World position of the center of cell `[x, y]`.
+    pub fn cell_center(self, x: usize, y: usize, cell_size: f32) -> Vec2 {
+        let (q, r) = (x as f32, y as f32);
+        let s = Self::hex_radius(cell_size);
+        match self {
+            GridTopology::Square => Vec2::new(q + 0.5, r + 0.5) * cell_size,
+            GridTopology::HexPointy => {
+                Vec2::new(SQRT_3 * (q + r / 2.0) * s, 1.5 * r * s) + Vec2::new(cell_size / 2.0, s)
+            }
+            GridTopology::HexFlat => {
+                Vec2::new(1.5 * q * s, SQRT_3 * (r + q / 2.0) * s) + Vec2::new(s, cell_size / 2.0)
+            }
+        }
+    }
+
+    /// Size of the box from the world origin that contains every cell.
+    pub fn board_size(self, width: usize, height: usize, cell_size: f32) -> Vec2 {
+        if width == 0 || height == 0 {
+            return Vec2::ZERO;
+        }
+        let far = self.cell_center(width - 1, height - 1, cell_size);
+        match self {
+            GridTopology::Square => Vec2::new(width as f32, height as f32) * cell_size,
+            GridTopology::HexPointy | GridTopology::HexFlat => {
+                far + self.cell_center(0, 0, cell_size)
+            }
+        }
+    }
+
+    /// The cell containing a world position, if it is on the board.
+    pub fn world_to_cell(
+        self,
+        board: &Board,
+        position: Vec2,
+        cell_size: f32,
+    ) -> Option<[usize; 2]> {
+        let s = Self::hex_radius(cell_size);
+        let (q, r) = match self {
+            GridTopology::Square => {
+                let cell = (position / cell_size).floor();
+                return board.checked(cell.x as isize, cell.y as isize);
+            }
+            GridTopology::HexPointy => {
+                let p = position - Vec2::new(cell_size / 2.0, s);
+                ((SQRT_3 / 3.0 * p.x - p.y / 3.0) / s, (2.0 / 3.0 * p.y) / s)
+            }
+            GridTopology::HexFlat => {
+                let p = position - Vec2::new(s, cell_size / 2.0);
+                ((2.0 / 3.0 * p.x) / s, (-p.x / 3.0 + SQRT_3 / 3.0 * p.y) / s)
+            }
+        };
+        // Round in cube coordinates, fixing whichever axis rounded furthest.
+        let cube = Vec3::new(q, r, -q - r);
+        let mut rounded = cube.round();
+        let diff = (rounded - cube).abs();
+        if diff.x > diff.y && diff.x > diff.z {
+            rounded.x = -rounded.y - rounded.z;
+        } else if diff.y > diff.z {
+            rounded.y = -rounded.x - rounded.z;
+        }
+        board.checked(rounded.x as isize, rounded.y as isize)
+    }
+}
+
+pub fn hex_mode(settings: Res<Settings>, topology: Res<GridTopology>) -> bool {
+    settings.backend == Backend::Cpu && topology.is_hex()
+}
+
+pub fn square_topology(topology: Res<GridTopology>) -> bool {
+    !topology.is_hex()
+}
+
+/// One hexagon mesh per cell, the hex counterpart of the cell sprites.
+#[derive(Component)]
+pub struct HexCell {
+    x: usize,
+    y: usize,
+}
+
+pub fn spawn_hex_cells(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    topology: Res<GridTopology>,
+) {
+    let cell_size = settings.cell_size;
+    // Slightly smaller than the lattice so neighboring cells stay distinct.
+    let mesh = meshes.add(Mesh::from(shape::RegularPolygon::new(
+        GridTopology::hex_radius(cell_size) * 0.95,
+        6,
+    )));
+    let rotation = match *topology {
+        GridTopology::HexFlat => Quat::from_rotation_z(std::f32::consts::FRAC_PI_6),
+        _ => Quat::IDENTITY,
+    };
+    for y in 0..game_data.board.height() {
+        for x in 0..game_data.board.width() {
+            commands.spawn((
+                MaterialMesh2dBundle {
+                    mesh: mesh.clone().into(),
+                    material: materials.add(ColorMaterial::from(settings.cell_color())),
+                    transform: Transform {
+                        translation: topology.cell_center(x, y, cell_size).extend(0.0),
+                        rotation,
+                        ..Default::default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                HexCell { x, y },
+                BoardEntity,
+            ));
+        }
+    }
+}
+
+pub fn render_hex_cells(
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut cells: Query<(&HexCell, &mut Visibility, &Handle<ColorMaterial>)>,
+) {
+    if !game_data.is_changed() && !settings.is_changed() {
+        return;
+    }
+    for (cell, mut visibility, material) in cells.iter_mut() {
+        let color = age::cell_color(&game_data, &settings, cell.x, cell.y);
+        let target = if color.is_some() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target {
+            *visibility = target;
+        }
+        if let Some(color) = color {
+            if materials.get(material).is_some_and(|m| m.color != color) {
+                materials.get_mut(material).unwrap().color = color;
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::camera::{CameraGlide, CursorCell, MainCamera};
+use crate::GameData;
+
+/// Converts a touch position to the bottom-left origin used by the cursor
+/// and camera. Bevy already flips touches on mobile targets but not on the
+/// web, where they arrive with a top-left origin.
+fn touch_viewport_position(window: &Window, position: Vec2) -> Vec2 {
+    if cfg!(target_arch = "wasm32") {
+        Vec2::new(position.x, window.height() - position.y)
+    } else {
+        position
+    }
+}
+
+/// State of the current single-finger gesture.
+#[derive(Default)]
+pub struct TouchStroke {
+    start: Option<[usize; 2]>,
+    dragged: bool,
+    /// Fingers that took part in a pinch, which don't paint until lifted.
+    pinched: HashSet<u64>,
+}
+
+/// A tap toggles the touched cell, dragging one finger paints live cells.
+pub fn touch_paint(
+    touches: Res<Touches>,
+    windows: Query<&Window>,
+    cursor: CursorCell,
+    mut game_data: ResMut<GameData>,
+    mut stroke: Local<TouchStroke>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    if touches.iter().count() > 1 {
+        // Two fingers are a pinch, not a stroke.
+        stroke.start = None;
+        stroke.dragged = false;
+        stroke
+            .pinched
+            .extend(touches.iter().map(|touch| touch.id()));
+        return;
+    }
+    for touch in touches.iter_just_pressed() {
+        stroke.pinched.remove(&touch.id());
+        stroke.start = cursor.cell_at(
+            &game_data.board,
+            touch_viewport_position(window, touch.position()),
+        );
+        stroke.dragged = false;
+    }
+    for touch in touches.iter() {
+        if stroke.pinched.contains(&touch.id()) {
+            continue;
+        }
+        let cell = cursor.cell_at(
+            &game_data.board,
+            touch_viewport_position(window, touch.position()),
+        );
+        if cell != stroke.start {
+            stroke.dragged = true;
+        }
+        if stroke.dragged {
+            for [x, y] in cell.into_iter().chain(stroke.start) {
+                game_data.board.set(x, y, true);
+            }
+        }
+    }
+    for touch in touches.iter_just_released() {
+        if stroke.pinched.remove(&touch.id()) {
+            continue;
+        }
+        if let (Some([x, y]), false) = (stroke.start, stroke.dragged) {
+            let alive = game_data.board.get(x, y);
+            game_data.board.set(x, y, !alive);
+        }
+        stroke.start = None;
+        stroke.dragged = false;
+    }
+    for touch in touches.iter_just_cancelled() {
+        stroke.pinched.remove(&touch.id());
+    }
+}
+
+/// Two fingers pinch to zoom and move together to pan.
+pub fn touch_pinch_zoom(
+    touches: Res<Touches>,
+    mut glide: ResMut<CameraGlide>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    let fingers: Vec<_> = touches.iter().take(2).collect();
+    let [first, second] = fingers[..] else {
+        return;
+    };
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+
+    let previous = first
+        .previous_position()
+        .distance(second.previous_position());
+    let current = first.position().distance(second.position());
+    if previous > 0.0 && current > 0.0 {
+        projection.scale = (projection.scale * previous / current).clamp(0.02, 50.0);
+        glide.zoomed(projection.scale);
+    }
+    let previous_center = (first.previous_position() + second.previous_position()) / 2.0;
+    let center = (first.position() + second.position()) / 2.0;
+    let delta = (center - previous_center) * projection.scale;
+    // Touch y grows downward on the web, the same direction as world y on
+    // the other targets after their flip.
+    let y_sign = if cfg!(target_arch = "wasm32") {
+        1.0
+    } else {
+        -1.0
+    };
+    transform.translation.x -= delta.x;
+    transform.translation.y += y_sign * delta.y;
+}
@@ -0,0 +1,231 @@
+//! Langton's Ant and other turmites walking over the board.
+//!
+//! Every cell has one of as many colors as the turn rule has letters. Color
+//! `0` is a dead cell and the rest are drawn as live ones. Each generation
+//! an ant turns according to the color under it, moves that cell on to the
+//! next color and steps forward.
+
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+use crate::board::BoundaryMode;
+use crate::config::{Automaton, Settings};
+use crate::GameData;
+
+const ANT_COLOR: Color = Color::rgb(0.9, 0.1, 0.1);
+
+/// Forward steps for the four headings, clockwise from up.
+const HEADINGS: [[isize; 2]; 4] = [[0, 1], [1, 0], [0, -1], [-1, 0]];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Turn {
+    Left,
+    Right,
+    Straight,
+    Back,
+}
+
+/// One turn per cell color, written as a string of `L`, `R`, `N` (no turn)
+/// and `U` such as Langton's `RL` or the symmetric `LLRR`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TurnRule(pub Vec<Turn>);
+
+impl Default for TurnRule {
+    fn default() -> Self {
+        "RL".parse().unwrap()
+    }
+}
+
+impl FromStr for TurnRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let turns = s
+            .trim()
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'L' => Ok(Turn::Left),
+                'R' => Ok(Turn::Right),
+                'N' => Ok(Turn::Straight),
+                'U' => Ok(Turn::Back),
+                _ => Err(format!("invalid turn `{c}`")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !(2..=u8::MAX as usize).contains(&turns.len()) {
+            return Err(format!("turn rule `{s}` needs between 2 and 255 turns"));
+        }
+        Ok(TurnRule(turns))
+    }
+}
+
+impl fmt::Display for TurnRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for turn in &self.0 {
+            let c = match turn {
+                Turn::Left => 'L',
+                Turn::Right => 'R',
+                Turn::Straight => 'N',
+                Turn::Back => 'U',
+            };
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Ant {
+    pub position: [usize; 2],
+    /// Index into [`HEADINGS`].
+    pub heading: usize,
+}
+
+#[derive(Default)]
+pub struct Turmites {
+    pub rule: TurnRule,
+    pub ants: Vec<Ant>,
+    /// Color of each cell, row by row like the board.
+    colors: Vec<u8>,
+}
+
+impl Turmites {
+    /// `count` ants spread evenly along the middle row, all facing up.
+    pub fn new(rule: TurnRule, count: usize, width: usize, height: usize) -> Self {
+        let ants = (0..count)
+            .map(|i| Ant {
+                position: [(i + 1) * width / (count + 1), height / 2],
+                heading: 0,
+            })
+            .collect();
+        Turmites {
+            rule,
+            ants,
+            colors: Vec::new(),
+        }
+    }
+}
+
+pub fn turmite_mode(game_data: Res<GameData>) -> bool {
+    game_data.automaton == Automaton::Turmite
+}
+
+/// The color of the cell at `[x, y]`, taking board edits that have not
+/// reached the colors yet into account.
+pub fn cell_color_index(game_data: &GameData, x: usize, y: usize) -> u8 {
+    let color = game_data
+        .turmites
+        .colors
+        .get(y * game_data.board.width() + x)
+        .copied()
+        .unwrap_or(0);
+    match (game_data.board.get(x, y), color) {
+        (false, _) => 0,
+        (true, 0) => 1,
+        (true, color) => color,
+    }
+}
+
+/// How a live cell of the given color is drawn: the cell color for two
+/// color rules, a spread of hues otherwise.
+pub fn display_color(game_data: &GameData, settings: &Settings, color: u8) -> Option<Color> {
+    let colors = game_data.turmites.rule.0.len();
+    match color {
+        0 => None,
+        _ if colors <= 2 => Some(settings.cell_color()),
+        _ => Some(Color::hsl(
+            360.0 * (color - 1) as f32 / (colors - 1) as f32,
+            0.7,
+            0.6,
+        )),
+    }
+}
+
+/// Moves every ant one step. Ants that walk off a
+/// [`BoundaryMode::DeadBorder`] board are gone.
+pub fn step(game_data: &mut GameData, boundary: BoundaryMode) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    let colors: Vec<u8> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| [x, y]))
+        .map(|[x, y]| cell_color_index(game_data, x, y))
+        .collect();
+    let GameData {
+        board, turmites, ..
+    } = game_data;
+    turmites.colors = colors;
+    let count = turmites.rule.0.len() as u8;
+    let Turmites {
+        rule, ants, colors, ..
+    } = turmites;
+    ants.retain_mut(|ant| {
+        let [x, y] = ant.position;
+        let color = &mut colors[y * width + x];
+        ant.heading = match rule.0[*color as usize % rule.0.len()] {
+            Turn::Left => (ant.heading + 3) % 4,
+            Turn::Right => (ant.heading + 1) % 4,
+            Turn::Straight => ant.heading,
+            Turn::Back => (ant.heading + 2) % 4,
+        };
+        *color = (*color + 1) % count;
+        board.set(x, y, *color != 0);
+        let [dx, dy] = HEADINGS[ant.heading];
+        match (
+            boundary.resolve(x as isize + dx, width),
+            boundary.resolve(y as isize + dy, height),
+        ) {
+            (Some(x), Some(y)) => {
+                ant.position = [x, y];
+                true
+            }
+            _ => false,
+        }
+    });
+}
+
+/// Marker for the sprite drawn over an ant.
+#[derive(Component)]
+pub struct AntMarker;
+
+/// Keeps one marker sprite per ant, drawn over the cells.
+pub fn render_ants(
+    mut commands: Commands,
+    game_data: Res<GameData>,
+    settings: Res<Settings>,
+    mut markers: Query<(Entity, &mut Transform), With<AntMarker>>,
+) {
+    if !game_data.is_changed() {
+        return;
+    }
+    let cell_size = settings.cell_size;
+    let ants = &game_data.turmites.ants;
+    let mut markers = markers.iter_mut();
+    for ant in ants {
+        let [x, y] = ant.position;
+        let translation = Vec3::new(
+            (x as f32 + 0.5) * cell_size,
+            (y as f32 + 0.5) * cell_size,
+            4.0,
+        );
+        match markers.next() {
+            Some((_, mut transform)) => transform.translation = translation,
+            None => {
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: ANT_COLOR,
+                            custom_size: Some(Vec2::splat(cell_size * 0.8)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(translation),
+                        ..Default::default()
+                    },
+                    AntMarker,
+                ));
+            }
+        }
+    }
+    for (entity, _) in markers {
+        commands.entity(entity).despawn();
+    }
+}
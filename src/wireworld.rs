@@ -0,0 +1,185 @@
+//! Wireworld: electrons running along conductors, for building logic
+//! circuits.
+//!
+//! The cell states live in [`GameData::wires`]. The board mirrors which cells
+//! are non-empty so drawing, selection and saving keep working; cells set
+//! on the board by other tools become conductors and cells cleared there
+//! become empty.
+
+use bevy::prelude::*;
+use rayon::prelude::*;
+
+use crate::bindings::{Action, Actions};
+use crate::board::BoundaryMode;
+use crate::brush::{Brush, Symmetry};
+use crate::camera::CursorCell;
+use crate::config::Automaton;
+use crate::topology::{self, GridTopology};
+use crate::GameData;
+
+const MOORE: [[isize; 2]; 8] = [
+    [-1, -1],
+    [0, -1],
+    [1, -1],
+    [-1, 0],
+    [1, 0],
+    [-1, 1],
+    [0, 1],
+    [1, 1],
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum WireState {
+    #[default]
+    Empty,
+    Conductor,
+    ElectronHead,
+    ElectronTail,
+}
+
+impl WireState {
+    pub fn color(self) -> Option<Color> {
+        match self {
+            WireState::Empty => None,
+            WireState::Conductor => Some(Color::rgb(1.0, 0.7, 0.1)),
+            WireState::ElectronHead => Some(Color::rgb(0.2, 0.5, 1.0)),
+            WireState::ElectronTail => Some(Color::rgb(1.0, 0.3, 0.2)),
+        }
+    }
+}
+
+/// The state the left mouse button paints.
+#[derive(Resource)]
+pub struct WirePalette(pub WireState);
+
+impl Default for WirePalette {
+    fn default() -> Self {
+        WirePalette(WireState::Conductor)
+    }
+}
+
+pub fn wireworld_mode(game_data: Res<GameData>) -> bool {
+    game_data.automaton == Automaton::Wireworld
+}
+
+/// The state of the cell at `[x, y]`, taking board edits that have not
+/// reached the wires yet into account.
+pub fn wire_state(game_data: &GameData, x: usize, y: usize) -> WireState {
+    let state = game_data
+        .wires
+        .get(y * game_data.board.width() + x)
+        .copied()
+        .unwrap_or_default();
+    match (game_data.board.get(x, y), state) {
+        (false, _) => WireState::Empty,
+        (true, WireState::Empty) => WireState::Conductor,
+        (true, state) => state,
+    }
+}
+
+/// Brings the wires in line with the board after edits made through it.
+fn sync_wires(game_data: &mut GameData) {
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    game_data.wires.resize(width * height, WireState::Empty);
+    for y in 0..height {
+        for x in 0..width {
+            game_data.wires[y * width + x] = wire_state(game_data, x, y);
+        }
+    }
+}
+
+fn set_wire(game_data: &mut GameData, x: usize, y: usize, state: WireState) {
+    let width = game_data.board.width();
+    game_data.wires[y * width + x] = state;
+    game_data.board.set(x, y, state != WireState::Empty);
+}
+
+/// Advances the circuit one generation: heads become tails, tails become
+/// conductors, and conductors next to one or two heads become heads.
+pub fn step(game_data: &mut GameData, boundary: BoundaryMode, topology: GridTopology) {
+    sync_wires(game_data);
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    if width == 0 {
+        return;
+    }
+    let offsets: &[[isize; 2]] = if topology.is_hex() {
+        &topology::HEX_NEIGHBORS
+    } else {
+        &MOORE
+    };
+    let wires = &game_data.wires;
+    let mut next = vec![WireState::Empty; wires.len()];
+    next.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+        for (x, cell) in row.iter_mut().enumerate() {
+            *cell = match wires[y * width + x] {
+                WireState::Empty => WireState::Empty,
+                WireState::ElectronHead => WireState::ElectronTail,
+                WireState::ElectronTail => WireState::Conductor,
+                WireState::Conductor => {
+                    let heads = offsets
+                        .iter()
+                        .filter(|[dx, dy]| {
+                            match (
+                                boundary.resolve(x as isize + dx, width),
+                                boundary.resolve(y as isize + dy, height),
+                            ) {
+                                (Some(x), Some(y)) => {
+                                    wires[y * width + x] == WireState::ElectronHead
+                                }
+                                _ => false,
+                            }
+                        })
+                        .count();
+                    if (1..=2).contains(&heads) {
+                        WireState::ElectronHead
+                    } else {
+                        WireState::Conductor
+                    }
+                }
+            };
+        }
+    });
+    game_data.wires = next;
+}
+
+/// `1`–`4` pick conductor, electron head, electron tail or empty to paint.
+pub fn select_wire_palette(actions: Actions, mut palette: ResMut<WirePalette>) {
+    let state = if actions.just_pressed(Action::WireConductor) {
+        WireState::Conductor
+    } else if actions.just_pressed(Action::WireHead) {
+        WireState::ElectronHead
+    } else if actions.just_pressed(Action::WireTail) {
+        WireState::ElectronTail
+    } else if actions.just_pressed(Action::WireEmpty) {
+        WireState::Empty
+    } else {
+        return;
+    };
+    palette.0 = state;
+    info!("painting {state:?}");
+}
+
+/// The Wireworld counterpart of `add_cells`: drawing paints
+/// the palette state with the current brush.
+pub fn paint_wires(
+    mut game_data: ResMut<GameData>,
+    palette: Res<WirePalette>,
+    brush: Res<Brush>,
+    symmetry: Res<Symmetry>,
+    cursor: CursorCell,
+    actions: Actions,
+) {
+    if !actions.pressed(Action::Draw) {
+        return;
+    }
+    let Some([x, y]) = cursor.cell(&game_data.board) else {
+        return;
+    };
+    sync_wires(&mut game_data);
+    let (width, height) = (game_data.board.width(), game_data.board.height());
+    for [dx, dy] in brush.offsets() {
+        for [x, y] in symmetry.images([x as isize + dx, y as isize + dy], width, height) {
+            set_wire(&mut game_data, x as usize, y as usize, palette.0);
+        }
+    }
+}